@@ -0,0 +1,42 @@
+use std::{hint::black_box, time::Instant};
+
+use rand::SeedableRng as _;
+use sudoku::{Grid, Variant};
+
+fn main() {
+    let iterations = 1_000;
+    let mut rng = rand::rngs::SmallRng::from_rng(&mut rand::rng());
+
+    let solutions: Vec<Grid> = (0..iterations)
+        .map(|_| sudoku::generate(&mut rng))
+        .collect();
+    let puzzles: Vec<Grid> = solutions
+        .iter()
+        .map(|solution| Variant::new().remove_givens(solution, &mut rng))
+        .collect();
+
+    for (name, grids) in [("solutions", &solutions), ("puzzles", &puzzles)] {
+        let mut durations = grids
+            .iter()
+            .map(|grid| {
+                let start = Instant::now();
+                black_box(grid.canonicalize());
+                start.elapsed().as_secs_f64() * 1000.0
+            })
+            .collect::<Vec<_>>();
+        durations.sort_unstable_by(|a, b| a.total_cmp(b));
+
+        println!(
+            "canonicalized {iterations} {name} in {:.2} seconds",
+            durations.iter().sum::<f64>() / 1000.0,
+        );
+        println!(
+            "min: {:.2} us, median: {:.2} us, p90: {:.2} us, p99: {:.2} us, max: {:.2} us",
+            1000.0 * durations[0],
+            1000.0 * durations[iterations / 2],
+            1000.0 * durations[iterations * 9 / 10],
+            1000.0 * durations[iterations * 99 / 100],
+            1000.0 * durations[iterations - 1],
+        );
+    }
+}
@@ -0,0 +1,15 @@
+use std::time::Instant;
+
+fn main() {
+    let iterations = 100_000;
+    let seed = rand::random();
+
+    let start = Instant::now();
+    let grids = sudoku::par_generate(seed, iterations);
+
+    println!(
+        "generated {} sudokus with seed {seed} in {:.2} seconds",
+        grids.len(),
+        start.elapsed().as_secs_f64(),
+    );
+}
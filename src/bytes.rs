@@ -0,0 +1,262 @@
+//! Compact binary encoding of grids.
+//!
+//! A grid is encoded as a sequence of digits with varying radices, which are
+//! combined into one big number (little endian, without trailing zero bytes):
+//!
+//! - The first digit (radix 2) is 0 for solved grids, and 1 for puzzles.
+//! - Solved grids are filled in box by box. Every box is split into three
+//!   lines: rows in the first band, and columns in the other bands. For every
+//!   box, the index of the partition of its numbers into the lines is stored,
+//!   among the partitions that are still possible. Then for every line, the
+//!   index of the order of its numbers among the possible orders is stored.
+//! - For puzzles, every field has a digit (radix 2) telling whether it is
+//!   solved, followed by the number (radix 9) if it is.
+//!
+//! For solved grids, this has at most `9! * 56 * 6^6` possibilities for the
+//! first band, `56^3 * 6^3 * 2^3` for the second band and `6^3 * 2^3` for the
+//! third band. This is less than `2^79` in total, so together with the first
+//! digit the number fits into 10 bytes.
+
+use crate::{Field, Grid, Number, Pos};
+
+impl Grid {
+    /// Encodes the solved fields of the grid as bytes. Solved grids need at
+    /// most 10 bytes, puzzles need 11 bytes plus about 0.4 bytes per solved
+    /// field.
+    ///
+    /// Possibilities of unsolved fields are not stored: [Grid::from_bytes]
+    /// creates the grid with [Grid::new] from the solved fields.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use sudoku::Grid;
+    ///
+    /// let grid = sudoku::generate(&mut rand::rng());
+    /// let bytes = grid.to_bytes();
+    /// assert!(bytes.len() <= 10);
+    /// assert_eq!(Grid::from_bytes(&bytes), Some(grid));
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let digits = self.solved_digits().unwrap_or_else(|| self.puzzle_digits());
+
+        let mut result = Vec::new();
+        for &(digit, radix) in digits.iter().rev() {
+            mul_add(&mut result, radix, digit);
+        }
+        result
+    }
+
+    /// Decodes a grid encoded with [Grid::to_bytes]. Returns `None` if the
+    /// bytes are not a valid encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Grid> {
+        let mut n = bytes.to_vec();
+        let grid = if div_rem(&mut n, 2) == 0 {
+            let mut grid = Grid::full();
+            for b in 0..9 {
+                let lines = box_lines(b);
+                let partitions = partitions(&grid, &lines);
+                let index = div_rem(&mut n, partitions.len().max(1) as u16);
+                let sets = *partitions.get(index as usize)?;
+                for (line, set) in lines.iter().zip(sets) {
+                    let orders = orders(&grid, line, set);
+                    let index = div_rem(&mut n, orders.len().max(1) as u16);
+                    let nums = *orders.get(index as usize)?;
+                    for (&pos, num) in line.iter().zip(nums) {
+                        if grid.set(pos, num) {
+                            return None;
+                        }
+                    }
+                }
+            }
+            grid
+        } else {
+            let mut fields = [[Field::full(); 9]; 9];
+            for pos in Pos::iterator() {
+                if div_rem(&mut n, 2) == 1 {
+                    let num = div_rem(&mut n, 9) as u8 + 1;
+                    fields[pos.y() as usize][pos.x() as usize] = Field::new(num);
+                }
+            }
+            Grid::new(fields)
+        };
+
+        // the number must be used up completely
+        n.is_empty().then_some(grid)
+    }
+
+    /// Returns the digits and their radices for a solved grid, or `None` if
+    /// the grid isn't solved or invalid
+    fn solved_digits(&self) -> Option<Vec<(u16, u16)>> {
+        let mut digits = vec![(0, 2)];
+        let mut grid = Grid::full();
+        for b in 0..9 {
+            let lines = box_lines(b);
+            let mut nums = [[Number::N1; 3]; 3];
+            for (line, line_nums) in lines.iter().zip(&mut nums) {
+                for (&pos, num) in line.iter().zip(line_nums) {
+                    *num = self[pos].solution()?;
+                }
+            }
+            let sets = nums.map(|line_nums| line_nums.into_iter().collect::<Field>());
+
+            let partitions = partitions(&grid, &lines);
+            let index = partitions.iter().position(|p| *p == sets)?;
+            digits.push((index as u16, partitions.len() as u16));
+            for ((line, set), line_nums) in lines.iter().zip(sets).zip(nums) {
+                let orders = orders(&grid, line, set);
+                let index = orders.iter().position(|o| *o == line_nums)?;
+                digits.push((index as u16, orders.len() as u16));
+                for (&pos, num) in line.iter().zip(line_nums) {
+                    if grid.set(pos, num) {
+                        return None;
+                    }
+                }
+            }
+        }
+        Some(digits)
+    }
+
+    /// Returns the digits and their radices for a puzzle
+    fn puzzle_digits(&self) -> Vec<(u16, u16)> {
+        let mut digits = vec![(1, 2)];
+        for pos in Pos::iterator() {
+            match self[pos].solution() {
+                Some(num) => digits.extend([(1, 2), (num.as_u8() as u16 - 1, 9)]),
+                None => digits.push((0, 2)),
+            }
+        }
+        digits
+    }
+}
+
+/// Returns the fields of box `b` as three lines, which are rows in the first
+/// band and columns in the other bands
+fn box_lines(b: u8) -> [[Pos; 3]; 3] {
+    let (x, y) = (b % 3 * 3, b / 3 * 3);
+    [0, 1, 2].map(|i| {
+        [0, 1, 2].map(|j| {
+            if b < 3 {
+                Pos::new(x + j, y + i)
+            } else {
+                Pos::new(x + i, y + j)
+            }
+        })
+    })
+}
+
+/// The bits of all sets of 3 numbers
+const TRIPLES: [u16; 84] = {
+    let mut triples = [0; 84];
+    let (mut bits, mut i) = (0u16, 0);
+    while bits < 0x200 {
+        if bits.count_ones() == 3 {
+            triples[i] = bits;
+            i += 1;
+        }
+        bits += 1;
+    }
+    triples
+};
+
+/// Returns the sets of 3 numbers in `field`
+fn triples(field: Field) -> impl Iterator<Item = Field> {
+    TRIPLES
+        .into_iter()
+        .filter(move |&bits| bits & !field.bits() == 0)
+        .map(Field::from_bits)
+}
+
+/// Returns the partitions of all numbers into the lines, where every line
+/// only gets numbers that are possible in one of its fields
+fn partitions(grid: &Grid, lines: &[[Pos; 3]; 3]) -> Vec<[Field; 3]> {
+    let possible = lines.map(|line| {
+        line.iter()
+            .fold(Field::empty(), |acc, &pos| acc | grid[pos])
+    });
+    let mut result = Vec::new();
+    for a in triples(possible[0]) {
+        for b in triples(possible[1] - a) {
+            let c = Field::full() - a - b;
+            if (c - possible[2]).is_empty() {
+                result.push([a, b, c]);
+            }
+        }
+    }
+    result
+}
+
+/// Returns the orders of the numbers in `set` along the line, where every
+/// number is possible in its field
+fn orders(grid: &Grid, line: &[Pos; 3], set: Field) -> Vec<[Number; 3]> {
+    const PERMUTATIONS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    let nums: Vec<Number> = set.possibilities().collect();
+    if nums.len() != 3 {
+        return Vec::new();
+    }
+    PERMUTATIONS
+        .iter()
+        .map(|p| p.map(|i| nums[i]))
+        .filter(|order| {
+            line.iter()
+                .zip(order)
+                .all(|(&pos, &num)| grid[pos].contains(num))
+        })
+        .collect()
+}
+
+/// Computes `n * radix + digit`, where `n` is a little endian number
+fn mul_add(n: &mut Vec<u8>, radix: u16, digit: u16) {
+    let mut carry = digit as u32;
+    for byte in n.iter_mut() {
+        let value = *byte as u32 * radix as u32 + carry;
+        *byte = value as u8;
+        carry = value >> 8;
+    }
+    while carry > 0 {
+        n.push(carry as u8);
+        carry >>= 8;
+    }
+}
+
+/// Divides the little endian number `n` by `radix` and returns the remainder
+fn div_rem(n: &mut Vec<u8>, radix: u16) -> u16 {
+    let mut rem = 0u32;
+    for byte in n.iter_mut().rev() {
+        let value = rem << 8 | *byte as u32;
+        *byte = (value / radix as u32) as u8;
+        rem = value % radix as u32;
+    }
+    while n.last() == Some(&0) {
+        n.pop();
+    }
+    rem as u16
+}
+
+#[test]
+fn test_round_trip() {
+    for seed in 0..100 {
+        let grid = crate::generate_from_seed(seed);
+        let bytes = grid.to_bytes();
+        assert!(bytes.len() <= 10);
+        assert_eq!(Grid::from_bytes(&bytes), Some(grid.clone()));
+
+        let mut fields = [[Field::full(); 9]; 9];
+        for pos in Pos::iterator().step_by(4) {
+            fields[pos.y() as usize][pos.x() as usize] = grid[pos];
+        }
+        let puzzle = Grid::new(fields);
+        assert_eq!(Grid::from_bytes(&puzzle.to_bytes()), Some(puzzle));
+    }
+
+    let empty = Grid::full();
+    assert_eq!(Grid::from_bytes(&empty.to_bytes()), Some(empty));
+    assert_eq!(Grid::from_bytes(&[0xff; 30]), None);
+}
@@ -0,0 +1,461 @@
+use std::cmp::Ordering;
+
+use crate::{Grid, Pos, Transform};
+
+/// All permutations of 3 elements
+static PERMS: [[u8; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+/// Returns whether the first `n` columns of `cols` can start the first of the
+/// column orders that only differ in the order of the columns in `empty`,
+/// which contain no solved fields: Such columns must stay in ascending order
+/// within their stack, and stacks only containing such columns must stay in
+/// ascending order.
+fn is_canonical_order(cols: &[u8], empty: u16) -> bool {
+    let is_empty = |c: u8| empty & (1 << c) != 0;
+    let is_empty_stack = |c: u8| empty >> (c / 3 * 3) & 0b111 == 0b111;
+    (0..cols.len()).all(|i| {
+        (i + 1..cols.len()).all(|j| {
+            let (a, b) = (cols[i], cols[j]);
+            !(is_empty(a)
+                && is_empty(b)
+                && a > b
+                && (a / 3 == b / 3 || (is_empty_stack(a) && is_empty_stack(b))))
+        })
+    })
+}
+
+/// Returns the positions of the solved fields in a row with the given mask of
+/// solved fields, after reordering its columns with `cols`. The first column
+/// is the most significant bit. Since numbers are labeled in order of
+/// appearance, a smaller pattern means a smaller first row.
+fn pattern(mask: u16, cols: &[u8]) -> u16 {
+    cols.iter().fold(0, |p, &col| p << 1 | (mask >> col & 1))
+}
+
+/// Returns the smallest [pattern] of the stacks in `stacks` (a mask of 3
+/// bits) of a row with the given mask of solved fields: The stacks are ordered
+/// by their number of solved fields, and within each stack, the unsolved
+/// fields come first.
+fn min_pattern(mask: u16, stacks: u8) -> u16 {
+    let mut counts = [0; 3];
+    let mut k = 0;
+    for stack in (0..3).filter(|&s| stacks & (1 << s) != 0) {
+        counts[k] = (mask >> (stack * 3) & 0b111).count_ones();
+        k += 1;
+    }
+    counts[..k].sort_unstable();
+    counts[..k].iter().fold(0, |p, &n| p << 3 | ((1 << n) - 1))
+}
+
+/// A depth-first search for the minlex form. For each choice of the first two
+/// rows, the columns are ordered stack by stack, and the search continues row
+/// by row for each column order. Every prefix is pruned as soon as it can only
+/// lead to results greater than the best result so far.
+struct Minlex {
+    /// The values of the grid (or its transposition), 0 for unsolved fields
+    values: [[u8; 9]; 9],
+    transpose: bool,
+    /// The mask of solved fields of each row
+    masks: [u16; 9],
+    /// The [min_pattern] of each row, for every mask of stacks
+    min_patterns: [[u16; 8]; 9],
+    /// The column of each number in each row, or 9 if it's not in the row
+    columns: [[u8; 10]; 9],
+    /// Bit `r` is set if row `r` contains no solved fields. Such rows are
+    /// interchangeable, so only their first order is searched.
+    empty_rows: u16,
+    /// Bit `c` is set if column `c` contains no solved fields, see
+    /// [is_canonical_order]
+    empty_cols: u16,
+    cols: [u8; 9],
+    /// The position of each column in the result, the inverse of `cols`
+    positions: [u8; 9],
+    /// The [pattern] of the first row of the best result so far
+    first: u16,
+    /// The other rows of the best result so far, `u8::MAX` where none were
+    /// found yet. The first row is only compared by `first`.
+    best: [[u8; 9]; 9],
+    /// The transform that produces `best`
+    result: Option<Transform>,
+}
+
+impl Minlex {
+    fn new() -> Self {
+        Minlex {
+            values: [[0; 9]; 9],
+            transpose: false,
+            masks: [0; 9],
+            min_patterns: [[0; 8]; 9],
+            columns: [[9; 10]; 9],
+            empty_rows: 0,
+            empty_cols: 0,
+            cols: [0; 9],
+            positions: [0; 9],
+            first: u16::MAX,
+            best: [[u8::MAX; 9]; 9],
+            result: None,
+        }
+    }
+
+    /// Searches the grid with the given values, or its transposition,
+    /// keeping the best result of previous searches
+    fn search_grid(&mut self, values: [[u8; 9]; 9], transpose: bool) {
+        self.values = values;
+        self.transpose = transpose;
+        self.masks = [0; 9];
+        self.columns = [[9; 10]; 9];
+        for (row, line) in values.iter().enumerate() {
+            for (col, &v) in line.iter().enumerate() {
+                if v != 0 {
+                    self.masks[row] |= 1 << col;
+                    self.columns[row][v as usize] = col as u8;
+                }
+            }
+        }
+        self.min_patterns = self
+            .masks
+            .map(|mask| [0, 1, 2, 3, 4, 5, 6, 7].map(|s| min_pattern(mask, s)));
+        self.empty_rows = (0..9)
+            .filter(|&row| self.masks[row] == 0)
+            .fold(0, |m, row| m | 1 << row);
+        self.empty_cols = (0..9)
+            .filter(|&col| self.masks.iter().all(|m| m & (1 << col) == 0))
+            .fold(0, |m, col| m | 1 << col);
+
+        let mut firsts = self.next_rows(0, &[0; 9], 0);
+        while firsts != 0 {
+            let first = firsts.trailing_zeros() as u8;
+            firsts &= firsts - 1;
+
+            let mut seconds = self.next_rows(1, &[first; 9], 1 << first);
+            while seconds != 0 {
+                let second = seconds.trailing_zeros() as u8;
+                seconds &= seconds - 1;
+                self.search_columns(first, second, 0, 0);
+            }
+        }
+    }
+
+    /// Returns a mask of the rows that can be placed at row `depth` without
+    /// breaking up bands, and without repeating an equivalent order of empty
+    /// rows
+    fn next_rows(&self, depth: usize, rows: &[u8; 9], used: u16) -> u16 {
+        let mut next = if depth.is_multiple_of(3) {
+            0b111_111_111
+        } else {
+            0b111 << (rows[depth - 1] / 3 * 3)
+        } & !used;
+
+        let mut empty_band_seen = false;
+        for band in [0b111, 0b111 << 3, 0b111 << 6] {
+            let empty = self.empty_rows & next & band;
+            if empty == 0 {
+                continue;
+            }
+            // keep only the first empty row of the band, and only the first
+            // band that is completely empty
+            next &= !empty | (empty & empty.wrapping_neg());
+            if depth.is_multiple_of(3) && self.empty_rows & band == band {
+                if empty_band_seen {
+                    next &= !band;
+                }
+                empty_band_seen = true;
+            }
+        }
+        next
+    }
+
+    /// Tries every order of the stacks that are not in `placed`, and of the
+    /// columns within them, starting at `stack`
+    fn search_columns(&mut self, first: u8, second: u8, stack: usize, placed: u8) {
+        if stack == 3 {
+            self.search_second(first, second);
+            return;
+        }
+
+        for source in (0..3).filter(|&s| placed & (1 << s) == 0) {
+            if self.is_pruned(first, second, stack * 3, placed, 1 << source) {
+                continue;
+            }
+            for perm in PERMS {
+                for (i, &p) in perm.iter().enumerate() {
+                    let col = source * 3 + p;
+                    self.cols[stack * 3 + i] = col;
+                    self.positions[col as usize] = (stack * 3 + i) as u8;
+                }
+                let placed = placed | 1 << source;
+                if !self.is_pruned(first, second, stack * 3 + 3, placed, 0) {
+                    self.search_columns(first, second, stack + 1, placed);
+                }
+            }
+        }
+    }
+
+    /// Returns whether the first `n` columns of `cols`, which consist of the
+    /// `placed` stacks, can only lead to results greater than `best`, or to
+    /// results that are also reached with another column order. The stacks
+    /// in `next` (at most one) follow in an order that is not decided yet.
+    fn is_pruned(&self, first: u8, second: u8, n: usize, placed: u8, next: u8) -> bool {
+        if self.empty_cols != 0 && !is_canonical_order(&self.cols[..n], self.empty_cols) {
+            return true;
+        }
+
+        let mask = self.masks[first as usize];
+        let prefix = pattern(mask, &self.cols[..n]);
+        let rest = !(placed | next) & 0b111;
+        let min_patterns = &self.min_patterns[first as usize];
+        let min = prefix << (9 - n)
+            | min_patterns[next as usize] << (3 * rest.count_ones())
+            | min_patterns[rest as usize];
+        match min.cmp(&self.first) {
+            Ordering::Greater => return true,
+            Ordering::Less => return false,
+            // the first row can at best be equal, so check the second row
+            Ordering::Equal => {}
+        }
+
+        let solved = prefix.count_ones() as u8;
+        let solved_next = (min_patterns[next as usize]).count_ones() as u8;
+        // labels of numbers that are not in the first row
+        let mut label = mask.count_ones() as u8;
+        for c in 0..n {
+            let v = self.values[second as usize][self.cols[c] as usize] as usize;
+            // the label, and whether it's exact or a lower bound
+            let (value, exact) = match (v, self.columns[first as usize][v]) {
+                (0, _) => (0, true),
+                (_, 9) => {
+                    label += 1;
+                    (label, true)
+                }
+                (_, col) if placed & (1 << (col / 3)) != 0 => {
+                    let position = self.positions[col as usize] as usize;
+                    ((prefix >> (n - position)).count_ones() as u8 + 1, true)
+                }
+                (_, col) if next & (1 << (col / 3)) != 0 => (solved + 1, false),
+                _ => (solved + solved_next + 1, false),
+            };
+            match value.cmp(&self.best[1][c]) {
+                Ordering::Greater => return true,
+                Ordering::Equal if exact => {}
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    /// Tries the first two rows with the current column order.
+    ///
+    /// The labels of the first row follow from its pattern, so the second
+    /// row is compared without labeling the first row: A number in the first
+    /// row is labeled by the number of solved fields before it, plus 1.
+    fn search_second(&mut self, first: u8, second: u8) {
+        let cols = self.cols;
+        let pattern = pattern(self.masks[first as usize], &cols);
+        if pattern > self.first {
+            return;
+        }
+        let less = pattern < self.first;
+        if less {
+            self.first = pattern;
+            self.best[1..].fill([u8::MAX; 9]);
+        }
+
+        let mut label = pattern.count_ones() as u8;
+        let mut line = [0; 9];
+        let mut ord = Ordering::Equal;
+        for (c, &col) in cols.iter().enumerate() {
+            let v = self.values[second as usize][col as usize] as usize;
+            if v != 0 {
+                line[c] = match self.columns[first as usize][v] {
+                    9 => {
+                        label += 1;
+                        label
+                    }
+                    col => (pattern >> (9 - self.positions[col as usize])).count_ones() as u8 + 1,
+                };
+            }
+            if ord == Ordering::Equal {
+                ord = line[c].cmp(&self.best[1][c]);
+                if ord == Ordering::Greater {
+                    return;
+                }
+            }
+        }
+        if ord == Ordering::Less {
+            self.best[1] = line;
+            self.best[2..].fill([u8::MAX; 9]);
+        }
+
+        // labels[0] is the last label handed out
+        let mut labels = [0; 10];
+        for row in [first, second] {
+            for col in cols {
+                let v = self.values[row as usize][col as usize] as usize;
+                if v != 0 && labels[v] == 0 {
+                    labels[0] += 1;
+                    labels[v] = labels[0];
+                }
+            }
+        }
+        let rows = [first, second, 0, 0, 0, 0, 0, 0, 0];
+        let used = 1 << first | 1 << second;
+        self.search(2, rows, used, labels, less || ord == Ordering::Less);
+    }
+
+    /// Returns the transform with the given rows and labels, and the current
+    /// column order. Numbers that don't appear get the remaining labels in
+    /// order.
+    fn transform(&self, rows: [u8; 9], mut labels: [u8; 10]) -> Transform {
+        for v in 1..10 {
+            if labels[v] == 0 {
+                labels[0] += 1;
+                labels[v] = labels[0];
+            }
+        }
+        let mut digits = [0; 9];
+        for (d, label) in digits.iter_mut().zip(&labels[1..]) {
+            *d = label - 1;
+        }
+        Transform::from_parts(digits, rows, self.cols, self.transpose)
+    }
+
+    /// Tries every row at row `depth`, given the rows and labels so far.
+    /// `less` is set if an earlier row is smaller than the same row of `best`.
+    fn search(&mut self, depth: usize, rows: [u8; 9], used: u16, labels: [u8; 10], less: bool) {
+        if depth == 9 {
+            if less {
+                self.result = Some(self.transform(rows, labels));
+            }
+            return;
+        }
+
+        let mut next = self.next_rows(depth, &rows, used);
+        while next != 0 {
+            let row = next.trailing_zeros() as u8;
+            next &= next - 1;
+
+            let mut labels = labels;
+            let mut line = [0; 9];
+            let mut ord = Ordering::Equal;
+            for (c, &col) in self.cols.iter().enumerate() {
+                let v = self.values[row as usize][col as usize] as usize;
+                if v != 0 {
+                    if labels[v] == 0 {
+                        labels[0] += 1;
+                        labels[v] = labels[0];
+                    }
+                    line[c] = labels[v];
+                }
+                if ord == Ordering::Equal {
+                    ord = line[c].cmp(&self.best[depth][c]);
+                    if ord == Ordering::Greater {
+                        break;
+                    }
+                }
+            }
+            if ord == Ordering::Greater {
+                continue;
+            }
+            if ord == Ordering::Less {
+                self.best[depth] = line;
+                self.best[depth + 1..].fill([u8::MAX; 9]);
+            }
+
+            let mut rows = rows;
+            rows[depth] = row;
+            let less = less || ord == Ordering::Less;
+            self.search(depth + 1, rows, used | 1 << row, labels, less);
+        }
+    }
+}
+
+impl Grid {
+    /// Returns the canonical form of this grid, together with the transform
+    /// that maps this grid to it.
+    ///
+    /// The canonical form is the minimal lexicographic representative (minlex)
+    /// among all grids that can be produced with a [Transform]: The solved
+    /// fields are read row by row, with unsolved fields counting as 0, and the
+    /// smallest such sequence is chosen. Two grids are equivalent if and only
+    /// if their canonical forms have the same solved fields.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use sudoku::Transform;
+    ///
+    /// let grid = sudoku::generate(&mut rand::rng());
+    /// let disguised = Transform::rotate_cw().apply(&grid);
+    ///
+    /// let (canonical, transform) = grid.canonicalize();
+    /// let (canonical2, _) = disguised.canonicalize();
+    /// assert_eq!(canonical, canonical2);
+    /// assert_eq!(canonical, transform.apply(&grid));
+    /// ```
+    pub fn canonicalize(&self) -> (Grid, Transform) {
+        // the values of the grid and its transposition, 0 for unsolved fields
+        let mut values = [[[0u8; 9]; 9]; 2];
+        for pos in Pos::iterator() {
+            let n = self[pos].solution().map_or(0, |n| n.as_u8());
+            values[0][pos.y() as usize][pos.x() as usize] = n;
+            values[1][pos.x() as usize][pos.y() as usize] = n;
+        }
+
+        let mut search = Minlex::new();
+        search.search_grid(values[0], false);
+        search.search_grid(values[1], true);
+
+        let transform = search.result.unwrap();
+        (transform.apply(self), transform)
+    }
+}
+
+#[test]
+fn test_canonicalize() {
+    let mut rng = rand::rng();
+    for _ in 0..5 {
+        let grid = crate::generate(&mut rng);
+        let mut puzzle = Grid::full();
+        for pos in Pos::iterator().step_by(3) {
+            puzzle.set(pos, grid[pos].solution().unwrap());
+        }
+
+        for grid in [grid, puzzle] {
+            let (canonical, transform) = grid.canonicalize();
+            let applied = transform.apply(&grid);
+            let other = Transform::random(&mut rng).apply(&grid);
+            let (other_canonical, _) = other.canonicalize();
+            assert_eq!(canonical, applied);
+            for pos in Pos::iterator() {
+                assert_eq!(canonical[pos].solution(), other_canonical[pos].solution());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_canonicalize_minimal() {
+    use crate::formats::{format_line, parse_line};
+
+    // compared with a search over all transforms
+    let grid = parse_line(
+        "187359624523864791469172800310927540094030100052410300945080200071093400030040910",
+        1,
+    )
+    .unwrap();
+    assert_eq!(
+        format_line(&grid.canonicalize().0, "."),
+        ".......12..1..2.34243156789....231753725148965....8423.3...526116.24.358825361947"
+    );
+
+    let (canonical, transform) = Grid::full().canonicalize();
+    assert_eq!(canonical, Grid::full());
+    assert_eq!(transform, Transform::identity());
+}
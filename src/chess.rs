@@ -0,0 +1,174 @@
+use crate::{Constraint, Eliminations, Grid, Number, Pos};
+
+/// Identical numbers must not be a knight's move apart.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{AntiKnight, Grid, Number, Pos, Variant};
+///
+/// let variant = Variant::new().with(AntiKnight);
+/// let mut grid = Grid::full();
+/// variant.set(&mut grid, Pos::new(4, 4), Number::N5);
+///
+/// assert!(!grid[Pos::new(6, 5)].contains(Number::N5));
+/// assert!(grid[Pos::new(6, 6)].contains(Number::N5));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AntiKnight;
+
+/// Identical numbers must not be a king's move apart, i.e. diagonally
+/// adjacent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AntiKing;
+
+/// The given number must not appear twice on a diagonal line, i.e. a queen's
+/// move apart. Rows and columns already contain every number once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AntiQueen(pub Number);
+
+const KNIGHT_MOVES: [(i8, i8); 8] = [
+    (1, 2),
+    (2, 1),
+    (2, -1),
+    (1, -2),
+    (-1, -2),
+    (-2, -1),
+    (-2, 1),
+    (-1, 2),
+];
+
+const KING_MOVES: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
+
+/// Returns the positions reached from `pos` by moving `distance` times in
+/// one of the directions, if they are inside the grid
+fn moves(pos: Pos, directions: &[(i8, i8)], distance: i8) -> impl Iterator<Item = Pos> + '_ {
+    let (x, y) = (pos.x() as i8, pos.y() as i8);
+    directions
+        .iter()
+        .map(move |&(dx, dy)| (x + dx * distance, y + dy * distance))
+        .filter(|&(x, y)| (0..9).contains(&x) && (0..9).contains(&y))
+        .map(|(x, y)| Pos::new(x as u8, y as u8))
+}
+
+/// Returns whether no number is attacked by the same number
+fn is_valid(grid: &Grid, attacked: impl Fn(Pos, Number) -> Vec<Pos>) -> bool {
+    Pos::iterator().all(|pos| {
+        grid[pos].solution().is_none_or(|num| {
+            attacked(pos, num)
+                .into_iter()
+                .all(|other| grid[other].solution() != Some(num))
+        })
+    })
+}
+
+impl Constraint for AntiKnight {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        for other in moves(pos, &KNIGHT_MOVES, 1) {
+            eliminations.remove(other, num);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        is_valid(grid, |pos, _| moves(pos, &KNIGHT_MOVES, 1).collect())
+    }
+}
+
+impl Constraint for AntiKing {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        for other in moves(pos, &KING_MOVES, 1) {
+            eliminations.remove(other, num);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        is_valid(grid, |pos, _| moves(pos, &KING_MOVES, 1).collect())
+    }
+}
+
+impl AntiQueen {
+    fn attacked(self, pos: Pos, num: Number) -> Vec<Pos> {
+        if num != self.0 {
+            return Vec::new();
+        }
+        (1..9)
+            .flat_map(|distance| moves(pos, &KING_MOVES, distance))
+            .collect()
+    }
+}
+
+impl Constraint for AntiQueen {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        for other in self.attacked(pos, num) {
+            eliminations.remove(other, num);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        is_valid(grid, |pos, num| self.attacked(pos, num))
+    }
+}
+
+#[test]
+fn test_chess_constraints() {
+    use crate::variant::{check_generated, eliminated};
+    use crate::{Field, SolutionCount, Variant, count_solutions_with_variant};
+
+    check_generated(&Variant::new().with(AntiKnight), 3);
+    check_generated(&Variant::new().with(AntiKing), 4);
+    check_generated(&Variant::new().with(AntiQueen(Number::N9)), 5);
+
+    // returns the fields where `num` was ruled out after placing it at `pos`
+    let full = Grid::full();
+    let removed = |constraint: &dyn Constraint, pos: Pos, num: Number| -> Vec<Pos> {
+        let grid = eliminated(&full, |e| constraint.on_placement(&full, pos, num, e)).unwrap();
+        Pos::iterator().filter(|&p| grid[p] != full[p]).collect()
+    };
+    let corner = Pos::new(0, 0);
+    let center = Pos::new(4, 4);
+    assert_eq!(removed(&AntiKnight, corner, Number::N1).len(), 2);
+    assert!(removed(&AntiKnight, corner, Number::N1).contains(&Pos::new(1, 2)));
+    assert_eq!(removed(&AntiKnight, center, Number::N1).len(), 8);
+    assert_eq!(removed(&AntiKing, corner, Number::N1), [Pos::new(1, 1)]);
+    let king = removed(&AntiKing, center, Number::N1);
+    assert_eq!(king.len(), 4);
+    assert!(king.contains(&Pos::new(5, 5)) && !king.contains(&Pos::new(5, 4)));
+    assert!(removed(&AntiQueen(Number::N9), corner, Number::N1).is_empty());
+    assert_eq!(removed(&AntiQueen(Number::N9), corner, Number::N9).len(), 8);
+    assert_eq!(
+        removed(&AntiQueen(Number::N9), center, Number::N9).len(),
+        16
+    );
+
+    // only solved fields are compared
+    let with_nines = |positions: [Pos; 2]| {
+        let mut grid = Grid::full();
+        for pos in positions {
+            grid[pos] = Field::new(9);
+        }
+        grid
+    };
+    let knight = with_nines([corner, Pos::new(1, 2)]);
+    assert!(!AntiKnight.is_valid(&knight));
+    assert!(AntiKing.is_valid(&knight));
+    let king = with_nines([corner, Pos::new(1, 1)]);
+    assert!(AntiKnight.is_valid(&king));
+    assert!(!AntiKing.is_valid(&king));
+    let queen = with_nines([corner, Pos::new(2, 2)]);
+    assert!(AntiKing.is_valid(&queen));
+    assert!(!AntiQueen(Number::N9).is_valid(&queen));
+    assert!(AntiQueen(Number::N1).is_valid(&queen));
+
+    let mut grid = Grid::full();
+    grid.set(Pos::new(0, 0), Number::N9);
+    grid.set(Pos::new(4, 4), Number::N9);
+    let variant = Variant::new().with(AntiQueen(Number::N9));
+    assert_eq!(
+        count_solutions_with_variant(&grid, &variant, 1),
+        SolutionCount::Exact(0)
+    );
+    assert_eq!(
+        count_solutions_with_variant(&grid, &Variant::new().with(AntiQueen(Number::N1)), 1),
+        SolutionCount::AtLeast(1)
+    );
+}
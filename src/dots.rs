@@ -0,0 +1,438 @@
+use crate::{Constraint, Edge, Eliminations, Field, Grid, Number, Pos};
+
+/// Kropki dots between adjacent fields: The numbers next to a black dot have
+/// a ratio of 2, the numbers next to a white dot differ by 1. Between 1 and 2,
+/// either dot can be placed.
+///
+/// With the negative constraint, all dots are given, so adjacent numbers
+/// without a dot must neither have a ratio of 2 nor differ by 1.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Edge, Field, Kropki, Pos, Variant};
+///
+/// let kropki = Kropki::new([Edge::right(Pos::new(0, 0))], []);
+/// let grid = Variant::new().with(kropki).new_grid([[Field::full(); 9]; 9]);
+///
+/// // 5, 7 and 9 have neither a half nor a double
+/// assert_eq!(grid[Pos::new(0, 0)], Field::from_bits(0b010_101_111));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Kropki {
+    black: Vec<Edge>,
+    white: Vec<Edge>,
+    negative: bool,
+}
+
+/// X and V between adjacent fields: The numbers next to an X add up to 10,
+/// the numbers next to a V add up to 5.
+///
+/// With the negative constraint, all X's and V's are given, so adjacent
+/// numbers without one must not add up to 5 or 10.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct XV {
+    x: Vec<Edge>,
+    v: Vec<Edge>,
+    negative: bool,
+}
+
+/// Orthogonally adjacent numbers must not be consecutive, i.e. differ by 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NonConsecutive;
+
+/// Greater-than signs between adjacent fields. Each sign points from the
+/// greater number to the smaller number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreaterThan(Vec<(Pos, Pos)>);
+
+fn is_double(a: u8, b: u8) -> bool {
+    a * 2 == b || b * 2 == a
+}
+
+fn is_consecutive(a: u8, b: u8) -> bool {
+    a.abs_diff(b) == 1
+}
+
+fn is_x(a: u8, b: u8) -> bool {
+    a + b == 10
+}
+
+fn is_v(a: u8, b: u8) -> bool {
+    a + b == 5
+}
+
+/// Returns the numbers in `field` that are related to a different number in
+/// `other`. Adjacent fields share a row or column, so they can't be equal.
+fn supported(field: Field, other: Field, related: impl Fn(u8, u8) -> bool) -> Field {
+    field
+        .possibilities()
+        .filter(|&a| {
+            (other - Field::from(a))
+                .possibilities()
+                .any(|b| related(a.as_u8(), b.as_u8()))
+        })
+        .collect()
+}
+
+/// Rules out numbers in `a` and `b` that aren't related to any number on the
+/// other side
+fn restrict_pair(
+    grid: &Grid,
+    (a, b): (Pos, Pos),
+    related: impl Fn(u8, u8) -> bool,
+    eliminations: &mut Eliminations,
+) {
+    eliminations.restrict(a, supported(grid[a], grid[b], &related));
+    eliminations.restrict(b, supported(grid[b], grid[a], |x, y| related(y, x)));
+}
+
+/// Removes the numbers related to `num` from the fields next to `pos`, except
+/// across the marked edges
+fn remove_unmarked(
+    pos: Pos,
+    num: Number,
+    marked: &[&[Edge]],
+    related: impl Fn(u8, u8) -> bool,
+    eliminations: &mut Eliminations,
+) {
+    for edge in Edge::around(pos) {
+        if !marked.iter().any(|edges| edges.contains(&edge)) {
+            let other = edge.other(pos);
+            for n in Field::full().possibilities() {
+                if related(num.as_u8(), n.as_u8()) {
+                    eliminations.remove(other, n);
+                }
+            }
+        }
+    }
+}
+
+/// Returns whether the numbers on both sides are solved and related
+fn is_related(grid: &Grid, (a, b): (Pos, Pos), related: impl Fn(u8, u8) -> bool) -> bool {
+    match (grid[a].solution(), grid[b].solution()) {
+        (Some(a), Some(b)) => related(a.as_u8(), b.as_u8()),
+        _ => false,
+    }
+}
+
+/// Collects two disjoint lists of edges. Panics if an edge is in both lists.
+fn marks(
+    first: impl IntoIterator<Item = Edge>,
+    second: impl IntoIterator<Item = Edge>,
+) -> (Vec<Edge>, Vec<Edge>) {
+    let first: Vec<Edge> = first.into_iter().collect();
+    let second: Vec<Edge> = second.into_iter().collect();
+    if let Some(edge) = second.iter().find(|edge| first.contains(edge)) {
+        panic!("{edge:?} is marked twice");
+    }
+    (first, second)
+}
+
+impl Kropki {
+    /// Creates Kropki dots. Panics if an edge has both a black and a white
+    /// dot.
+    pub fn new(
+        black: impl IntoIterator<Item = Edge>,
+        white: impl IntoIterator<Item = Edge>,
+    ) -> Self {
+        let (black, white) = marks(black, white);
+        Kropki {
+            black,
+            white,
+            negative: false,
+        }
+    }
+
+    /// Adds the negative constraint: All dots are given
+    pub fn with_negative_constraint(mut self) -> Self {
+        self.negative = true;
+        self
+    }
+
+    /// Returns the edges with a black dot
+    pub fn black(&self) -> &[Edge] {
+        &self.black
+    }
+
+    /// Returns the edges with a white dot
+    pub fn white(&self) -> &[Edge] {
+        &self.white
+    }
+
+    /// Returns whether the negative constraint applies
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+}
+
+impl Constraint for Kropki {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        if self.negative {
+            let related = |a, b| is_double(a, b) || is_consecutive(a, b);
+            remove_unmarked(pos, num, &[&self.black, &self.white], related, eliminations);
+        }
+    }
+
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        for edge in &self.black {
+            restrict_pair(grid, edge.positions(), is_double, eliminations);
+        }
+        for edge in &self.white {
+            restrict_pair(grid, edge.positions(), is_consecutive, eliminations);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        Edge::iterator().all(|edge| {
+            let pair = edge.positions();
+            if self.black.contains(&edge) {
+                is_related(grid, pair, is_double)
+            } else if self.white.contains(&edge) {
+                is_related(grid, pair, is_consecutive)
+            } else {
+                !self.negative
+                    || !is_related(grid, pair, |a, b| is_double(a, b) || is_consecutive(a, b))
+            }
+        })
+    }
+}
+
+impl XV {
+    /// Creates X's and V's. Panics if an edge has both an X and a V.
+    pub fn new(x: impl IntoIterator<Item = Edge>, v: impl IntoIterator<Item = Edge>) -> Self {
+        let (x, v) = marks(x, v);
+        XV {
+            x,
+            v,
+            negative: false,
+        }
+    }
+
+    /// Adds the negative constraint: All X's and V's are given
+    pub fn with_negative_constraint(mut self) -> Self {
+        self.negative = true;
+        self
+    }
+
+    /// Returns the edges with an X
+    pub fn x(&self) -> &[Edge] {
+        &self.x
+    }
+
+    /// Returns the edges with a V
+    pub fn v(&self) -> &[Edge] {
+        &self.v
+    }
+
+    /// Returns whether the negative constraint applies
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+}
+
+impl Constraint for XV {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        if self.negative {
+            let related = |a, b| is_x(a, b) || is_v(a, b);
+            remove_unmarked(pos, num, &[&self.x, &self.v], related, eliminations);
+        }
+    }
+
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        for edge in &self.x {
+            restrict_pair(grid, edge.positions(), is_x, eliminations);
+        }
+        for edge in &self.v {
+            restrict_pair(grid, edge.positions(), is_v, eliminations);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        Edge::iterator().all(|edge| {
+            let pair = edge.positions();
+            if self.x.contains(&edge) {
+                is_related(grid, pair, is_x)
+            } else if self.v.contains(&edge) {
+                is_related(grid, pair, is_v)
+            } else {
+                !self.negative || !is_related(grid, pair, |a, b| is_x(a, b) || is_v(a, b))
+            }
+        })
+    }
+}
+
+impl Constraint for NonConsecutive {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        remove_unmarked(pos, num, &[], is_consecutive, eliminations);
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        Edge::iterator().all(|edge| !is_related(grid, edge.positions(), is_consecutive))
+    }
+
+    fn generate_by_search(&self) -> bool {
+        true
+    }
+}
+
+impl GreaterThan {
+    /// Creates greater-than signs from pairs of a greater and a smaller
+    /// position. Panics if the positions of a pair aren't adjacent.
+    pub fn new(signs: impl IntoIterator<Item = (Pos, Pos)>) -> Self {
+        let signs: Vec<(Pos, Pos)> = signs.into_iter().collect();
+        for &(greater, smaller) in &signs {
+            Edge::new(greater, smaller);
+        }
+        GreaterThan(signs)
+    }
+
+    /// Returns the pairs of a greater and a smaller position
+    pub fn signs(&self) -> &[(Pos, Pos)] {
+        &self.0
+    }
+}
+
+impl Constraint for GreaterThan {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        for &pair in &self.0 {
+            restrict_pair(grid, pair, |a, b| a > b, eliminations);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        self.0
+            .iter()
+            .all(|&pair| is_related(grid, pair, |a, b| a > b))
+    }
+}
+
+#[test]
+fn test_dots() {
+    use crate::Variant;
+    use crate::variant::check_generated;
+
+    let grid = Variant::new()
+        .with(XV::new(
+            [Edge::below(Pos::new(3, 3))],
+            [Edge::right(Pos::new(0, 0))],
+        ))
+        .with(GreaterThan::new([(Pos::new(1, 0), Pos::new(2, 0))]))
+        .new_grid([[Field::full(); 9]; 9]);
+    assert_eq!(grid[Pos::new(3, 3)], Field::from_bits(0b111_101_111));
+    assert_eq!(grid[Pos::new(1, 0)], Field::from_bits(0b000_001_110));
+    assert_eq!(grid[Pos::new(2, 0)], Field::from_bits(0b000_000_111));
+
+    // only the non-consecutive constraint needs the randomized search
+    let variant = Variant::new().with(NonConsecutive);
+    assert!(variant.generate_by_search());
+    assert!(
+        !Variant::new()
+            .with(Kropki::new([], []))
+            .generate_by_search()
+    );
+    let solution = check_generated(&variant, 7);
+
+    // all dots of a solution, with the negative constraint
+    let black = Edge::iterator().filter(|&e| is_related(&solution, e.positions(), is_double));
+    let white = Edge::iterator()
+        .filter(|&e| is_related(&solution, e.positions(), is_consecutive))
+        .filter(|&e| !is_related(&solution, e.positions(), is_double));
+    let kropki = Kropki::new(black, white).with_negative_constraint();
+    assert!(kropki.is_valid(&solution));
+    assert!(!kropki.is_valid(&crate::generate_from_seed(0)));
+}
+
+#[test]
+fn test_dot_constraints() {
+    use crate::variant::eliminated;
+
+    // a grid where the fields (0, 0) and (1, 0) are set to `a` and `b`, with
+    // 0 for an unsolved field
+    let pair = |a: u8, b: u8| {
+        let mut grid = Grid::full();
+        for (x, n) in [(0, a), (1, b)] {
+            if n > 0 {
+                grid[Pos::new(x, 0)] = Field::new(n);
+            }
+        }
+        grid
+    };
+    let edge = Edge::right(Pos::new(0, 0));
+    let right = |grid: &Grid| grid[Pos::new(1, 0)];
+    let full = Grid::full();
+    let center = Pos::new(4, 4);
+
+    let black = Kropki::new([edge], []);
+    let white = Kropki::new([], [edge]);
+    let grid = pair(3, 0);
+    let restricted = |kropki: &Kropki| eliminated(&grid, |e| kropki.eliminations(&grid, e));
+    assert_eq!(right(&restricted(&black).unwrap()), Field::new(6));
+    assert_eq!(
+        right(&restricted(&white).unwrap()),
+        Field::from_bits(0b1010)
+    );
+    let grid = pair(1, 0);
+    let restricted = |kropki: &Kropki| eliminated(&grid, |e| kropki.eliminations(&grid, e));
+    assert_eq!(right(&restricted(&black).unwrap()), Field::new(2));
+    assert_eq!(right(&restricted(&white).unwrap()), Field::new(2));
+    let grid = pair(5, 0);
+    let grid = eliminated(&grid, |e| black.eliminations(&grid, e)).unwrap();
+    assert!(grid[Pos::new(0, 0)].is_empty());
+    assert!(black.is_valid(&pair(3, 6)));
+    assert!(!black.is_valid(&pair(3, 4)));
+    assert!(!black.is_valid(&pair(3, 0)));
+
+    // without dots, doubles and consecutive numbers are removed, except
+    // across edges with a dot
+    let negative = Kropki::new([], [Edge::right(center)]).with_negative_constraint();
+    let grid = eliminated(&full, |e| {
+        negative.on_placement(&full, center, Number::N4, e)
+    })
+    .unwrap();
+    assert_eq!(grid[Pos::new(4, 5)], Field::from_bits(0b101_101_001));
+    assert_eq!(grid[Pos::new(5, 4)], Field::full());
+    let negative = Kropki::new([], []).with_negative_constraint();
+    assert!(!negative.is_valid(&pair(2, 4)));
+    assert!(!negative.is_valid(&pair(2, 3)));
+    assert!(negative.is_valid(&pair(2, 5)));
+    assert!(Kropki::new([], []).is_valid(&pair(2, 4)));
+
+    let x = XV::new([edge], []);
+    let v = XV::new([], [edge]);
+    let grid = pair(3, 0);
+    let grid = eliminated(&grid, |e| x.eliminations(&grid, e)).unwrap();
+    assert_eq!(right(&grid), Field::new(7));
+    let grid = eliminated(&full, |e| v.eliminations(&full, e)).unwrap();
+    assert_eq!(grid[Pos::new(0, 0)], Field::from_bits(0b000_001_111));
+    assert_eq!(right(&grid), Field::from_bits(0b000_001_111));
+    assert!(x.is_valid(&pair(3, 7)));
+    assert!(!x.is_valid(&pair(3, 2)));
+    assert!(v.is_valid(&pair(3, 2)));
+    let negative = XV::new([], []).with_negative_constraint();
+    let grid = eliminated(&full, |e| {
+        negative.on_placement(&full, center, Number::N3, e)
+    })
+    .unwrap();
+    assert_eq!(grid[Pos::new(3, 4)], Field::from_bits(0b110_111_101));
+    assert!(!negative.is_valid(&pair(3, 7)));
+    assert!(negative.is_valid(&pair(3, 8)));
+
+    let grid = eliminated(&full, |e| {
+        NonConsecutive.on_placement(&full, Pos::new(0, 0), Number::N5, e)
+    })
+    .unwrap();
+    assert_eq!(right(&grid), Field::from_bits(0b111_010_111));
+    assert_eq!(grid[Pos::new(0, 1)], Field::from_bits(0b111_010_111));
+    assert_eq!(grid[Pos::new(1, 1)], Field::full());
+    assert!(!NonConsecutive.is_valid(&pair(1, 2)));
+    assert!(NonConsecutive.is_valid(&pair(1, 3)));
+    assert!(NonConsecutive.is_valid(&pair(1, 0)));
+
+    let greater = GreaterThan::new([(Pos::new(0, 0), Pos::new(1, 0))]);
+    let grid = pair(0, 7);
+    let grid = eliminated(&grid, |e| greater.eliminations(&grid, e)).unwrap();
+    assert_eq!(grid[Pos::new(0, 0)], Field::from_bits(0b110_000_000));
+    assert!(greater.is_valid(&pair(7, 3)));
+    assert!(!greater.is_valid(&pair(3, 7)));
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use crate::Pos;
+
+/// The border between two orthogonally adjacent positions, where e.g. a
+/// Kropki dot can be placed.
+///
+/// To iterate over all 144 edges, use [Edge::iterator()].
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Edge, Pos};
+///
+/// let edge = Edge::new(Pos::new(4, 1), Pos::new(4, 0));
+/// assert_eq!(edge, Edge::below(Pos::new(4, 0)));
+/// assert_eq!(edge.positions(), (Pos::new(4, 0), Pos::new(4, 1)));
+/// assert_eq!(Edge::around(Pos::new(0, 0)).count(), 2);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Edge {
+    /// The position left of or above `b`
+    a: Pos,
+    b: Pos,
+}
+
+impl Edge {
+    /// Creates the edge between two positions in any order. Panics if the
+    /// positions aren't orthogonally adjacent.
+    pub fn new(a: Pos, b: Pos) -> Self {
+        if a.x().abs_diff(b.x()) + a.y().abs_diff(b.y()) != 1 {
+            panic!("{a:?} and {b:?} are not adjacent");
+        }
+        Edge {
+            a: a.min(b),
+            b: a.max(b),
+        }
+    }
+
+    /// Returns the edge on the right side of the position. Panics if the
+    /// position is in the last column.
+    pub fn right(pos: Pos) -> Self {
+        Edge::new(pos, Pos::new(pos.x() + 1, pos.y()))
+    }
+
+    /// Returns the edge below the position. Panics if the position is in the
+    /// last row.
+    pub fn below(pos: Pos) -> Self {
+        Edge::new(pos, Pos::new(pos.x(), pos.y() + 1))
+    }
+
+    /// Returns an iterator over all edges in a 9x9 sudoku grid
+    pub fn iterator() -> impl Iterator<Item = Edge> {
+        let right = Pos::iterator().filter(|p| p.x() < 8).map(Edge::right);
+        let below = Pos::iterator().filter(|p| p.y() < 8).map(Edge::below);
+        right.chain(below)
+    }
+
+    /// Returns the 2 to 4 edges of the position
+    pub fn around(pos: Pos) -> impl Iterator<Item = Edge> {
+        let (x, y) = (pos.x() as i8, pos.y() as i8);
+        [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+            .into_iter()
+            .filter(|&(x, y)| (0..9).contains(&x) && (0..9).contains(&y))
+            .map(move |(x, y)| Edge::new(pos, Pos::new(x as u8, y as u8)))
+    }
+
+    /// Returns the two positions, the left or upper one first
+    pub fn positions(self) -> (Pos, Pos) {
+        (self.a, self.b)
+    }
+
+    /// Returns the position on the other side of the edge. Panics if `pos`
+    /// isn't on this edge.
+    pub fn other(self, pos: Pos) -> Pos {
+        if pos == self.a {
+            self.b
+        } else if pos == self.b {
+            self.a
+        } else {
+            panic!("{pos:?} is not on the edge {self:?}");
+        }
+    }
+}
+
+impl fmt::Debug for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}-{:?}", self.a, self.b)
+    }
+}
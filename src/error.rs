@@ -0,0 +1,83 @@
+use std::{error::Error, fmt};
+
+use crate::Pos;
+
+/// An error that occurred while parsing a sudoku from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    kind: ParseErrorKind,
+}
+
+/// The reason why parsing failed, see [ParseError].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// A character that is not allowed in this format
+    InvalidChar(char),
+    /// The number of rows, fields, or other items is wrong
+    InvalidLength { expected: usize, found: usize },
+    /// A required item is missing
+    Missing(&'static str),
+    /// Reading the input failed
+    Io(std::io::ErrorKind),
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, kind: ParseErrorKind) -> Self {
+        ParseError { line, kind }
+    }
+
+    /// Returns the line (starting with 1) where the error occurred
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the reason why parsing failed
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match self.kind {
+            ParseErrorKind::InvalidChar(c) => write!(f, "invalid character {c:?}"),
+            ParseErrorKind::InvalidLength { expected, found } => {
+                write!(f, "expected {expected} items, found {found}")
+            }
+            ParseErrorKind::Missing(item) => write!(f, "missing {item}"),
+            ParseErrorKind::Io(kind) => write!(f, "{}", std::io::Error::from(kind)),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// The reason why a region map is invalid, see
+/// [Regions::new](crate::Regions::new).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegionError {
+    /// The field has a region number greater than 8
+    OutOfRange(Pos),
+    /// The region doesn't contain exactly 9 fields
+    WrongSize { region: u8, size: usize },
+    /// The fields of the region aren't connected orthogonally
+    Disconnected(u8),
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionError::OutOfRange(pos) => write!(f, "region of field {pos:?} is out of range"),
+            RegionError::WrongSize { region, size } => {
+                write!(f, "region {region} has {size} fields instead of 9")
+            }
+            RegionError::Disconnected(region) => write!(f, "region {region} is not connected"),
+        }
+    }
+}
+
+impl Error for RegionError {}
@@ -1,7 +1,10 @@
 use std::fmt;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Sub, SubAssign};
 
 use crate::Number;
 
+const MASK: u16 = 0b111_111_111;
+
 /// A sudoku cell. It can contain multiple possibilities.
 ///
 /// ### Example
@@ -20,8 +23,12 @@ use crate::Number;
 /// cell.set(Number::N5);
 ///
 /// assert_eq!(cell.solution(), Some(Number::N5));
+///
+/// cell.insert(Number::N7);
+///
+/// assert_eq!(cell & !Field::new(5), Field::new(7));
 /// ```
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Field(u16);
 
 impl fmt::Debug for Field {
@@ -52,7 +59,24 @@ impl Field {
 
     /// Creates an unconstrained sudoku cell, which can contain any number
     pub fn full() -> Self {
-        Field(0b111_111_111)
+        Field(MASK)
+    }
+
+    /// Creates a sudoku cell without any possibilities
+    pub fn empty() -> Self {
+        Field(0)
+    }
+
+    /// Creates a sudoku cell from a raw bit mask, where bit `n - 1`
+    /// represents the number `n`. Bits above the 9th are ignored.
+    pub const fn from_bits(bits: u16) -> Self {
+        Field(bits & MASK)
+    }
+
+    /// Returns the raw bit mask of this sudoku cell, where bit `n - 1`
+    /// represents the number `n`
+    pub const fn bits(self) -> u16 {
+        self.0
     }
 
     /// Whether the sudoku cell is empty -- meaning that it cannot contain
@@ -119,8 +143,106 @@ impl Field {
         self.0 = n.as_bit();
     }
 
+    /// Adds `n` to the list of possibilities in this sudoku cell
+    pub fn insert(&mut self, n: Number) {
+        self.0 |= n.as_bit();
+    }
+
     /// Removes `n` from the list of possibilities in this sudoku cell
     pub fn remove(&mut self, n: Number) {
-        self.0 &= 0b111_111_111 - n.as_bit();
+        self.0 &= MASK - n.as_bit();
+    }
+}
+
+impl FromIterator<Number> for Field {
+    fn from_iter<T: IntoIterator<Item = Number>>(iter: T) -> Self {
+        let mut field = Field::empty();
+        field.extend(iter);
+        field
+    }
+}
+
+impl Extend<Number> for Field {
+    fn extend<T: IntoIterator<Item = Number>>(&mut self, iter: T) {
+        for n in iter {
+            self.insert(n);
+        }
+    }
+}
+
+impl From<Number> for Field {
+    fn from(n: Number) -> Self {
+        Field(n.as_bit())
+    }
+}
+
+/// Intersection: the numbers possible in both cells
+impl BitAnd for Field {
+    type Output = Field;
+
+    fn bitand(self, rhs: Field) -> Field {
+        Field(self.0 & rhs.0)
     }
 }
+
+impl BitAndAssign for Field {
+    fn bitand_assign(&mut self, rhs: Field) {
+        self.0 &= rhs.0;
+    }
+}
+
+/// Union: the numbers possible in either cell
+impl BitOr for Field {
+    type Output = Field;
+
+    fn bitor(self, rhs: Field) -> Field {
+        Field(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Field {
+    fn bitor_assign(&mut self, rhs: Field) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Difference: the numbers possible in `self`, but not in `rhs`
+impl Sub for Field {
+    type Output = Field;
+
+    fn sub(self, rhs: Field) -> Field {
+        Field(self.0 & !rhs.0)
+    }
+}
+
+impl SubAssign for Field {
+    fn sub_assign(&mut self, rhs: Field) {
+        self.0 &= !rhs.0;
+    }
+}
+
+/// Complement: the numbers _not_ possible in the cell
+impl Not for Field {
+    type Output = Field;
+
+    fn not(self) -> Field {
+        Field(!self.0 & MASK)
+    }
+}
+
+#[test]
+fn test_set_operations() {
+    let a: Field = [Number::N1, Number::N2, Number::N3].into_iter().collect();
+    let b: Field = [Number::N3, Number::N4].into_iter().collect();
+
+    assert_eq!((a & b).solution(), Some(Number::N3));
+    assert_eq!((a | b).len(), 4);
+    assert_eq!((a - b).bits(), 0b11);
+    assert_eq!((!a).len(), 6);
+    assert_eq!(!Field::full(), Field::empty());
+    assert_eq!(Field::from_bits(u16::MAX), Field::full());
+
+    let mut c = Field::empty();
+    c.insert(Number::N9);
+    assert_eq!(c, Field::from(Number::N9));
+}
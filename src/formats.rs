@@ -0,0 +1,634 @@
+//! Readers and writers for common sudoku file formats:
+//!
+//! - SadMan Sudoku (`.sdk`): a single puzzle with metadata and
+//!   [Restrictions]
+//! - SadMan Sudoku multi-puzzle (`.sdm`): one puzzle per line
+//! - Simple Sudoku (`.ss`): a single puzzle, optionally with box borders
+//! - OpenSudoku (`.opensudoku`): an XML collection of puzzles with metadata
+//!
+//! All readers return grids created with [Grid::new], so they can be passed
+//! to [solve](crate::solve) directly. Writers only store solved fields.
+
+use std::fmt;
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::pencil_marks::{layout, pencil_marks};
+use crate::restrictions::{format_allowed, parse_allowed};
+use crate::{Field, Grid, Number, Pos, Restrictions};
+
+/// Information about a puzzle or collection of puzzles. Which fields are
+/// supported depends on the format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metadata {
+    /// The title, e.g. the name of a collection
+    pub title: Option<String>,
+    /// The author of the puzzle
+    pub author: Option<String>,
+    /// A description of the puzzle
+    pub description: Option<String>,
+    /// A free-form comment
+    pub comment: Option<String>,
+    /// The difficulty level as written in the file, e.g. `Easy`
+    pub difficulty: Option<String>,
+    /// Where the puzzle was published
+    pub source: Option<String>,
+    /// A URL of the puzzle or its source
+    pub url: Option<String>,
+    /// The publication or creation date as written in the file
+    pub date: Option<String>,
+}
+
+/// A single puzzle with its restrictions and metadata.
+///
+/// When formatted with [Display](fmt::Display), the puzzle is laid out like
+/// [Grid::to_pencil_marks], with the solved fields, and the restrictions of
+/// the other fields like in [Restrictions].
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::formats::{Metadata, Puzzle};
+/// use sudoku::{Grid, Pos, Restrictions};
+///
+/// let puzzle = Puzzle {
+///     grid: Grid::full(),
+///     restrictions: Restrictions::new().with_even(Pos::new(0, 0)),
+///     metadata: Metadata::default(),
+/// };
+/// assert!(puzzle.to_string().lines().nth(1).unwrap().starts_with("| E  ."));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Puzzle {
+    pub grid: Grid,
+    /// The numbers allowed in each field, which are rules of the puzzle
+    /// like the givens
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub restrictions: Restrictions,
+    pub metadata: Metadata,
+}
+
+impl Puzzle {
+    /// Formats the puzzle like [Grid::to_pencil_marks], where the
+    /// possibilities of restricted fields are limited to the allowed numbers
+    /// and preceded by their restriction, e.g. `E26` for an even field where
+    /// 2 and 6 are possible. Because of the restrictions, the result can't be
+    /// parsed with [Grid::from_pencil_marks].
+    pub fn to_pencil_marks(&self) -> String {
+        let cells = std::array::from_fn(|y| {
+            std::array::from_fn(|x| {
+                let pos = Pos::new(x as u8, y as u8);
+                let allowed = self.restrictions.allowed(pos);
+                let marks = pencil_marks(self.grid[pos] & allowed);
+                if allowed == Field::full() {
+                    marks
+                } else {
+                    format_allowed(allowed) + &marks
+                }
+            })
+        });
+        layout(&cells)
+    }
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = std::array::from_fn(|y| {
+            std::array::from_fn(|x| {
+                let pos = Pos::new(x as u8, y as u8);
+                match self.grid[pos].solution() {
+                    Some(num) => num.as_str().to_string(),
+                    None => format_allowed(self.restrictions.allowed(pos)),
+                }
+            })
+        });
+        f.write_str(&layout(&cells))
+    }
+}
+
+/// A collection of puzzles with shared metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Collection {
+    pub grids: Vec<Grid>,
+    pub metadata: Metadata,
+}
+
+/// Parses a line of 81 fields, where `1` to `9` are solved fields and `.`,
+/// `0` and `_` are unsolved fields. `line` is the line number used in errors.
+pub(crate) fn parse_line(s: &str, line: usize) -> Result<Grid, ParseError> {
+    let mut fields = [[Field::full(); 9]; 9];
+    let mut len = 0;
+    for c in s.chars() {
+        let field = parse_cell(c, &['.', '0', '_'])
+            .ok_or_else(|| ParseError::new(line, ParseErrorKind::InvalidChar(c)))?;
+        if len < 81 {
+            fields[len / 9][len % 9] = field;
+        }
+        len += 1;
+    }
+
+    if len != 81 {
+        let kind = ParseErrorKind::InvalidLength {
+            expected: 81,
+            found: len,
+        };
+        return Err(ParseError::new(line, kind));
+    }
+    Ok(Grid::new(fields))
+}
+
+/// Parses a single field, which is either a number or one of the characters
+/// in `empty`
+fn parse_cell(c: char, empty: &[char]) -> Option<Field> {
+    match c {
+        '1'..='9' => Some(Field::new(c as u8 - b'0')),
+        _ if empty.contains(&c) => Some(Field::full()),
+        _ => None,
+    }
+}
+
+/// Returns the solved fields of a row, with `empty` for unsolved fields
+fn format_row(grid: &Grid, y: u8, empty: &'static str) -> String {
+    (0..9)
+        .map(|x| {
+            grid[Pos::new(x, y)]
+                .solution()
+                .map_or(empty, Number::as_str)
+        })
+        .collect()
+}
+
+/// Returns the solved fields of the grid as a line of 81 characters, with
+/// `empty` for unsolved fields
+pub(crate) fn format_line(grid: &Grid, empty: &'static str) -> String {
+    (0..9).map(|y| format_row(grid, y, empty)).collect()
+}
+
+/// Parses a SadMan Sudoku (`.sdk`) file.
+///
+/// Metadata lines start with `#` followed by a letter: `A` (author), `D`
+/// (description), `C` (comment), `B` (date), `S` (source), `L` (level, i.e.
+/// difficulty) and `U` (URL). The puzzle follows as 9 lines of 9 fields,
+/// optionally after a `[Puzzle]` header. An optional `[Restrictions]`
+/// section contains 9 lines of 9 fields written like in [Puzzle]'s
+/// [Display](fmt::Display), e.g. `E` for an even field. Other sections (such
+/// as `[State]`) are ignored.
+pub fn read_sdk(s: &str) -> Result<Puzzle, ParseError> {
+    let mut metadata = Metadata::default();
+    let mut fields = [[Field::full(); 9]; 9];
+    let mut row = 0;
+    let mut restrictions = Restrictions::new();
+    let mut restricted_rows = 0;
+    let mut section = "Puzzle";
+
+    for (li, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if let Some(tag) = line.strip_prefix('#') {
+            let mut chars = tag.chars();
+            let key = chars.next();
+            let value = chars.as_str().trim();
+            if value.is_empty() {
+                continue;
+            }
+            let value = Some(value.to_string());
+            match key {
+                Some('A') => metadata.author = value,
+                Some('D') => metadata.description = value,
+                Some('C') => metadata.comment = value,
+                Some('B') => metadata.date = value,
+                Some('S') => metadata.source = value,
+                Some('L') => metadata.difficulty = value,
+                Some('U') => metadata.url = value,
+                _ => {}
+            }
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name;
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let err = |kind| ParseError::new(li + 1, kind);
+        if section == "Restrictions" {
+            let allowed = parse_allowed(line).map_err(err)?;
+            if allowed.len() != 9 {
+                let kind = ParseErrorKind::InvalidLength {
+                    expected: 9,
+                    found: allowed.len(),
+                };
+                return Err(err(kind));
+            }
+            // extra rows are counted and reported after the last line
+            if let Some(fields) = restrictions.fields.get_mut(restricted_rows) {
+                fields.copy_from_slice(&allowed);
+            }
+            restricted_rows += 1;
+            continue;
+        }
+        if section != "Puzzle" {
+            continue;
+        }
+        let mut len = 0;
+        for c in line.chars() {
+            let field = parse_cell(c, &['.', '0', 'X'])
+                .ok_or_else(|| err(ParseErrorKind::InvalidChar(c)))?;
+            if row < 9 && len < 9 {
+                fields[row][len] = field;
+            }
+            len += 1;
+        }
+        if len != 9 {
+            let kind = ParseErrorKind::InvalidLength {
+                expected: 9,
+                found: len,
+            };
+            return Err(err(kind));
+        }
+        row += 1;
+    }
+
+    if row != 9 {
+        let kind = ParseErrorKind::InvalidLength {
+            expected: 9,
+            found: row,
+        };
+        return Err(ParseError::new(s.lines().count(), kind));
+    }
+    if restricted_rows != 0 && restricted_rows != 9 {
+        let kind = ParseErrorKind::InvalidLength {
+            expected: 9,
+            found: restricted_rows,
+        };
+        return Err(ParseError::new(s.lines().count(), kind));
+    }
+    Ok(Puzzle {
+        grid: Grid::new(fields),
+        restrictions,
+        metadata,
+    })
+}
+
+/// Formats a puzzle as a SadMan Sudoku (`.sdk`) file. The title is not
+/// supported by this format and is omitted.
+pub fn write_sdk(puzzle: &Puzzle) -> String {
+    let meta = &puzzle.metadata;
+    let tags = [
+        ('A', &meta.author),
+        ('D', &meta.description),
+        ('C', &meta.comment),
+        ('B', &meta.date),
+        ('S', &meta.source),
+        ('L', &meta.difficulty),
+        ('U', &meta.url),
+    ];
+
+    let mut result = String::new();
+    for (key, value) in tags {
+        if let Some(value) = value {
+            result.push('#');
+            result.push(key);
+            result.push_str(&value.replace('\n', " "));
+            result.push('\n');
+        }
+    }
+    result.push_str("[Puzzle]\n");
+    for y in 0..9 {
+        result.push_str(&format_row(&puzzle.grid, y, "."));
+        result.push('\n');
+    }
+    if !puzzle.restrictions.is_empty() {
+        result.push_str("[Restrictions]\n");
+        for row in &puzzle.restrictions.fields {
+            result.extend(row.iter().map(|&allowed| format_allowed(allowed)));
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// Parses a SadMan Sudoku multi-puzzle (`.sdm`) file, containing one puzzle
+/// of 81 fields per line. Empty lines are ignored.
+pub fn read_sdm(s: &str) -> Result<Vec<Grid>, ParseError> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(li, line)| parse_line(line.trim(), li + 1))
+        .collect()
+}
+
+/// Formats puzzles as a SadMan Sudoku multi-puzzle (`.sdm`) file.
+pub fn write_sdm(grids: &[Grid]) -> String {
+    let mut result = String::new();
+    for grid in grids {
+        result.push_str(&format_line(grid, "0"));
+        result.push('\n');
+    }
+    result
+}
+
+/// Parses a Simple Sudoku (`.ss`) file. Unsolved fields are written as `.`,
+/// `X` or `0`. Box borders made of `|`, `-`, `+` and `*` are ignored, as are
+/// empty lines.
+pub fn read_ss(s: &str) -> Result<Grid, ParseError> {
+    let mut fields = [[Field::full(); 9]; 9];
+    let mut row = 0;
+
+    for (li, line) in s.lines().enumerate() {
+        let err = |kind| ParseError::new(li + 1, kind);
+        let cells = line
+            .chars()
+            .filter(|c| !"|-+*".contains(*c) && !c.is_whitespace());
+
+        let mut len = 0;
+        for c in cells {
+            let field = parse_cell(c, &['.', '0', 'X'])
+                .ok_or_else(|| err(ParseErrorKind::InvalidChar(c)))?;
+            if len < 9 && row < 9 {
+                fields[row][len] = field;
+            }
+            len += 1;
+        }
+
+        if len != 0 {
+            if len != 9 {
+                let kind = ParseErrorKind::InvalidLength {
+                    expected: 9,
+                    found: len,
+                };
+                return Err(err(kind));
+            }
+            row += 1;
+        }
+    }
+
+    if row != 9 {
+        let kind = ParseErrorKind::InvalidLength {
+            expected: 9,
+            found: row,
+        };
+        return Err(ParseError::new(s.lines().count(), kind));
+    }
+    Ok(Grid::new(fields))
+}
+
+/// Formats a puzzle as a Simple Sudoku (`.ss`) file with box borders.
+pub fn write_ss(grid: &Grid) -> String {
+    let mut result = String::from("*-----------*\n");
+    for y in 0..9 {
+        let row = format_row(grid, y, ".");
+        result.push_str(&format!("|{}|{}|{}|\n", &row[0..3], &row[3..6], &row[6..9]));
+        if y == 2 || y == 5 {
+            result.push_str("|---+---+---|\n");
+        }
+    }
+    result.push_str("*-----------*\n");
+    result
+}
+
+/// Parses an OpenSudoku (`.opensudoku`) XML file. Each `<game>` element
+/// contains a puzzle in its `data` attribute. The collection's `<name>`,
+/// `<author>`, `<description>`, `<comment>`, `<created>`, `<source>`,
+/// `<level>` and `<sourceURL>` elements are read as metadata.
+pub fn read_opensudoku(s: &str) -> Result<Collection, ParseError> {
+    let element = |tag: &str| -> Option<String> {
+        let open = format!("<{tag}>");
+        let start = s.find(&open)? + open.len();
+        let end = start + s[start..].find(&format!("</{tag}>"))?;
+        let value = s[start..end].trim();
+        (!value.is_empty()).then(|| unescape(value))
+    };
+
+    let metadata = Metadata {
+        title: element("name"),
+        author: element("author"),
+        description: element("description"),
+        comment: element("comment"),
+        difficulty: element("level"),
+        source: element("source"),
+        url: element("sourceURL"),
+        date: element("created"),
+    };
+
+    let mut grids = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("<game") {
+        let offset = s.len() - rest.len() + start;
+        let line = s[..offset].matches('\n').count() + 1;
+        rest = &rest[start + "<game".len()..];
+        if !rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+            continue;
+        }
+
+        let end = rest.find('>').unwrap_or(rest.len());
+        let data = attribute(&rest[..end], "data")
+            .ok_or_else(|| ParseError::new(line, ParseErrorKind::Missing("data attribute")))?;
+        grids.push(parse_line(&unescape(data), line)?);
+        rest = &rest[end..];
+    }
+
+    Ok(Collection { grids, metadata })
+}
+
+/// Formats puzzles as an OpenSudoku (`.opensudoku`) XML file.
+pub fn write_opensudoku(collection: &Collection) -> String {
+    let meta = &collection.metadata;
+    let elements = [
+        ("name", &meta.title),
+        ("author", &meta.author),
+        ("description", &meta.description),
+        ("comment", &meta.comment),
+        ("created", &meta.date),
+        ("source", &meta.source),
+        ("level", &meta.difficulty),
+        ("sourceURL", &meta.url),
+    ];
+
+    let mut result = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opensudoku>\n");
+    for (tag, value) in elements {
+        if let Some(value) = value {
+            result.push_str(&format!("  <{tag}>{}</{tag}>\n", escape(value)));
+        }
+    }
+    for grid in &collection.grids {
+        let data = format_line(grid, "0");
+        result.push_str(&format!("  <game data=\"{data}\" />\n"));
+    }
+    result.push_str("</opensudoku>\n");
+    result
+}
+
+/// Returns the value of an XML attribute in the given tag contents
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    loop {
+        let start = rest.find(name)?;
+        let before = rest[..start].chars().next_back();
+        rest = &rest[start + name.len()..];
+        let after = rest.trim_start();
+        if before.is_some_and(char::is_whitespace)
+            && let Some(after) = after.strip_prefix('=')
+        {
+            let after = after.trim_start();
+            let quote = after.chars().next().filter(|&q| q == '"' || q == '\'')?;
+            let value = &after[1..];
+            return Some(&value[..value.find(quote)?]);
+        }
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[test]
+fn test_formats() {
+    let mut rng = rand::rng();
+    let solution = crate::generate(&mut rng);
+    let mut fields = [[Field::full(); 9]; 9];
+    for pos in Pos::iterator().step_by(2) {
+        fields[pos.y() as usize][pos.x() as usize] = solution[pos];
+    }
+    let grid = Grid::new(fields);
+
+    let metadata = Metadata {
+        author: Some("A & B".to_string()),
+        difficulty: Some("Hard".to_string()),
+        ..Metadata::default()
+    };
+    let mut puzzle = Puzzle {
+        grid: grid.clone(),
+        restrictions: Restrictions::new(),
+        metadata: metadata.clone(),
+    };
+    assert!(!write_sdk(&puzzle).contains("[Restrictions]"));
+    assert_eq!(read_sdk(&write_sdk(&puzzle)), Ok(puzzle.clone()));
+    puzzle.restrictions = Restrictions::new()
+        .with_odd(Pos::new(1, 0))
+        .with(Pos::new(8, 8), Field::from_bits(0b1_0000_0011));
+    assert_eq!(read_sdk(&write_sdk(&puzzle)), Ok(puzzle));
+    assert_eq!(read_ss(&write_ss(&grid)), Ok(grid.clone()));
+
+    let grids = vec![grid, solution];
+    assert_eq!(read_sdm(&write_sdm(&grids)), Ok(grids.clone()));
+
+    let collection = Collection {
+        grids,
+        metadata: Metadata {
+            title: Some("<Daily>".to_string()),
+            ..metadata
+        },
+    };
+    assert_eq!(
+        read_opensudoku(&write_opensudoku(&collection)),
+        Ok(collection)
+    );
+}
+
+#[test]
+fn test_read_formats() {
+    let sdk = "#AJohn Doe\n#LEasy\n#C\n[Puzzle]\n..3.2.6..\n9..3.5..1\n..18.64..\n..81.29..\n7.......8\n..67.82..\n..26.95..\n8..2.3..9\n..5.1.3..\n";
+    let puzzle = read_sdk(sdk).unwrap();
+    assert_eq!(puzzle.metadata.author.as_deref(), Some("John Doe"));
+    assert_eq!(puzzle.metadata.comment, None);
+    assert_eq!(puzzle.grid[Pos::new(2, 0)], Field::new(3));
+
+    let ss = "..3|.2.|6..\n9..|3.5|..1\n..1|8.6|4..\n---+---+---\n..8|1.2|9..\n7..|...|..8\n..6|7.8|2..\n---+---+---\n..2|6.9|5..\n8..|2.3|..9\n..5|.1.|3..\n";
+    assert_eq!(read_ss(ss), Ok(puzzle.grid.clone()));
+
+    let xml = r#"<?xml version="1.0"?>
+<opensudoku>
+  <name>Test</name>
+  <author> </author>
+  <game data='003020600900305001001806400008102900700000008006708200002609500800203009005010300' />
+  <game data="123" />
+</opensudoku>"#;
+    let err = read_opensudoku(xml).unwrap_err();
+    assert_eq!(err.line(), 6);
+    let xml = xml.replace("<game data=\"123\" />", "");
+    let collection = read_opensudoku(&xml).unwrap();
+    assert_eq!(collection.metadata.title.as_deref(), Some("Test"));
+    assert_eq!(collection.metadata.author, None);
+    assert_eq!(collection.grids, vec![puzzle.grid]);
+
+    let restrictions = "[Restrictions]\nE[12]O......\n";
+    let err = read_sdk(&format!("{sdk}{restrictions}")).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ParseErrorKind::InvalidLength {
+            expected: 9,
+            found: 1
+        }
+    );
+    let rows = "E[12]O......\n".repeat(9);
+    let err = read_sdk(&format!("{sdk}{restrictions}{rows}")).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ParseErrorKind::InvalidLength {
+            expected: 9,
+            found: 10
+        }
+    );
+    let err = read_sdk(&format!("{sdk}..5.1.3..\n..5.1.3..\n")).unwrap_err();
+    assert_eq!(
+        err.kind(),
+        &ParseErrorKind::InvalidLength {
+            expected: 9,
+            found: 11
+        }
+    );
+    let restrictions = "[Restrictions]\nE[]O......\n";
+    let err = read_sdk(&format!("{sdk}{restrictions}")).unwrap_err();
+    assert_eq!(err.line(), 15);
+    assert_eq!(err.kind(), &ParseErrorKind::InvalidChar(']'));
+}
+
+#[test]
+fn test_display_puzzle() {
+    let mut grid = Grid::full();
+    grid.set(Pos::new(1, 0), Number::N4);
+    let puzzle = Puzzle {
+        grid,
+        restrictions: Restrictions::new()
+            .with_even(Pos::new(0, 0))
+            .with_even(Pos::new(1, 0))
+            .with(Pos::new(2, 0), Field::from_bits(0b111)),
+        metadata: Metadata::default(),
+    };
+
+    let text = puzzle.to_string();
+    assert_eq!(text.lines().count(), 13);
+    assert!(
+        text.lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("| E  4  [123] | .")
+    );
+
+    // the grid has no solved fields besides the 4, so the possibilities
+    // only differ from the pencil marks of the grid in restricted fields
+    let text = puzzle.to_pencil_marks();
+    let first_row: Vec<&str> = text.lines().nth(1).unwrap().split_whitespace().collect();
+    assert_eq!(
+        first_row[..6],
+        ["|", "E268", "E4", "[123]123", "|", "12356789"]
+    );
+    let mut plain = puzzle.clone();
+    plain.restrictions = Restrictions::new();
+    assert_eq!(plain.to_pencil_marks(), puzzle.grid.to_pencil_marks());
+}
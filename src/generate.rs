@@ -1,6 +1,5 @@
-use rand::seq::IndexedRandom;
-
-use crate::{Grid, Number, Pos, solve::brute_force};
+use crate::solve::{brute_force, random_solution};
+use crate::{Grid, Number, Pos, Variant};
 
 /// Generates a full sudoku grid that is
 ///
@@ -13,6 +12,58 @@ use crate::{Grid, Number, Pos, solve::brute_force};
 /// Warning: The number of empty squares is NOT a good indicator for the
 /// Sudoku's difficulty.
 pub fn generate(rng: &mut impl rand::Rng) -> Grid {
+    generate_with(&Variant::new(), &mut |len| rng.random_range(..len))
+}
+
+/// Generates a full sudoku grid like [generate], but following the rules of
+/// `variant`. If a constraint needs it, see
+/// [Constraint::generate_by_search](crate::Constraint::generate_by_search),
+/// a randomized search is used instead, so not every grid is equally likely.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Variant, generate_with_variant};
+///
+/// let variant = Variant::windoku();
+/// let grid = generate_with_variant(&variant, &mut rand::rng());
+/// assert!(variant.is_valid(&grid));
+/// ```
+pub fn generate_with_variant(variant: &Variant, rng: &mut impl rand::Rng) -> Grid {
+    generate_with(variant, &mut |len| rng.random_range(..len))
+}
+
+/// Generates a full sudoku grid like [generate], but deterministically from a
+/// seed. The same seed produces the same grid on every platform and in every
+/// version of this crate, so it can be used to share puzzles by their seed.
+///
+/// The random numbers don't come from [rand], but from a SplitMix64
+/// generator (Steele, Lea and Flood, 2014) seeded with `seed`. A random
+/// index below `n` is computed from the next 64-bit output `x` as the upper
+/// 64 bits of `x * n`, rejecting outputs where the lower 64 bits are less
+/// than `2^64 mod n` (Lemire, 2019).
+///
+/// ### Example
+///
+/// ```
+/// let grid = sudoku::generate_from_seed(1234);
+/// assert_eq!(grid, sudoku::generate_from_seed(1234));
+/// assert_eq!(grid.best_field_to_guess(), None);
+/// ```
+pub fn generate_from_seed(seed: u64) -> Grid {
+    let mut rng = SplitMix64(seed);
+    generate_with(&Variant::new(), &mut |len| rng.below(len))
+}
+
+/// Generates a grid following the rules of `variant`, where
+/// `random_index(len)` returns a random index below `len`
+pub(crate) fn generate_with(
+    variant: &Variant,
+    random_index: &mut impl FnMut(usize) -> usize,
+) -> Grid {
+    if variant.generate_by_search() {
+        return generate_variant(variant, random_index);
+    }
     let mut pos_acc = Vec::with_capacity(81);
     let mut num_acc = Vec::with_capacity(9);
     let mut grid_acc = Vec::new();
@@ -22,13 +73,15 @@ pub fn generate(rng: &mut impl rand::Rng) -> Grid {
         let mut was_valid = false;
 
         for _ in 0..26 {
-            if let AddedResult::Failed = add_random(&mut grid, &mut pos_acc, &mut num_acc, rng) {
+            if let AddedResult::Failed =
+                add_random(&mut grid, variant, &mut pos_acc, &mut num_acc, random_index)
+            {
                 continue 'outer;
             }
         }
 
         grid_acc.clear();
-        brute_force(&grid, &mut grid_acc, 2);
+        brute_force(&grid, variant, &mut grid_acc, 2);
         if grid_acc.len() == 1 {
             return grid_acc.pop().unwrap();
         } else if grid_acc.is_empty() {
@@ -37,14 +90,15 @@ pub fn generate(rng: &mut impl rand::Rng) -> Grid {
 
         loop {
             let mut copy = grid.clone();
-            match add_random(&mut copy, &mut pos_acc, &mut num_acc, rng) {
+            match add_random(&mut copy, variant, &mut pos_acc, &mut num_acc, random_index) {
                 AddedResult::Success => {}
                 AddedResult::Failed => continue,
-                AddedResult::Full => return copy,
+                AddedResult::Full if variant.is_valid(&copy) => return copy,
+                AddedResult::Full => continue 'outer,
             }
 
             grid_acc.clear();
-            brute_force(&copy, &mut grid_acc, 2);
+            brute_force(&copy, variant, &mut grid_acc, 2);
             if grid_acc.len() == 1 {
                 return grid_acc.pop().unwrap();
             } else if grid_acc.is_empty() {
@@ -60,6 +114,27 @@ pub fn generate(rng: &mut impl rand::Rng) -> Grid {
     }
 }
 
+/// The number of guesses after which [generate_variant] starts over
+const MAX_VARIANT_GUESSES: u64 = 300;
+
+/// Generates a grid with a randomized depth-first search, for variants with
+/// so few solutions that random clues almost always contradict each other.
+/// The search often runs into dead ends that take very long to explore, so
+/// it is restarted after too many guesses.
+fn generate_variant(variant: &Variant, random_index: &mut impl FnMut(usize) -> usize) -> Grid {
+    loop {
+        let solution = random_solution(
+            &Grid::full(),
+            Some(variant),
+            MAX_VARIANT_GUESSES,
+            random_index,
+        );
+        if let Some(solution) = solution {
+            return solution;
+        }
+    }
+}
+
 enum AddedResult {
     Success,
     Failed,
@@ -68,23 +143,84 @@ enum AddedResult {
 
 fn add_random(
     grid: &mut Grid,
+    variant: &Variant,
     pos_acc: &mut Vec<Pos>,
     num_acc: &mut Vec<Number>,
-    rng: &mut impl rand::Rng,
+    random_index: &mut impl FnMut(usize) -> usize,
 ) -> AddedResult {
     grid.all_unsolved_fields(pos_acc);
-    let Some(&pos) = pos_acc.choose(rng) else {
+    if pos_acc.is_empty() {
         return AddedResult::Full;
-    };
+    }
+    let pos = pos_acc[random_index(pos_acc.len())];
 
     num_acc.clear();
     num_acc.extend(grid[pos].possibilities());
-    let &num = num_acc.choose(rng).unwrap();
+    let num = num_acc[random_index(num_acc.len())];
 
-    let is_empty = grid.set(pos, num);
+    let is_empty = variant.set(grid, pos, num);
     if is_empty {
         AddedResult::Failed
     } else {
         AddedResult::Success
     }
 }
+
+/// The SplitMix64 generator, which must never change, see
+/// [generate_from_seed]
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        let n = n as u64;
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let product = self.next() as u128 * n as u128;
+            if product as u64 >= threshold {
+                return (product >> 64) as usize;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generate_from_seed() {
+    let mut rng = SplitMix64(1234567);
+    assert_eq!(rng.next(), 6457827717110365317);
+    assert_eq!(rng.next(), 3203168211198807973);
+
+    let golden = [
+        (
+            0,
+            "731429685859361724642578319187692453263845197495137268976254831314986572528713946",
+        ),
+        (
+            1,
+            "492371865537268941168594723681927354975643218243185679856719432714832596329456187",
+        ),
+        (
+            1234,
+            "124369578589742361673851492796235184241698735358174629917526843435987216862413957",
+        ),
+        (
+            u64::MAX,
+            "952183467341967825678245913425378196169452738783691254534729681216834579897516342",
+        ),
+    ];
+    for (seed, expected) in golden {
+        let grid = generate_from_seed(seed);
+        assert_eq!(
+            crate::formats::format_line(&grid, "."),
+            expected,
+            "seed {seed}"
+        );
+    }
+}
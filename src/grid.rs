@@ -1,10 +1,13 @@
 use std::fmt;
 use std::ops::{Index, IndexMut};
 
+use crate::solve::Stats;
 use crate::{Field, Number, Pos};
 
 /// A sudoku grid, where each cell can contain multiple possibilities.
-#[derive(Clone)]
+///
+/// Grids are compared and ordered field by field, row by row.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Grid {
     pub(super) fields: [[Field; 9]; 9],
 }
@@ -59,9 +62,31 @@ impl Grid {
     /// - If the cell is now empty, `true` is returned
     /// - If the cell now contains exactly 1 possibility, set it recursively
     pub fn set(&mut self, pos: Pos, num: Number) -> bool {
-        let visible = pos.get_visible_positions();
+        self.set_with_stats(pos, num, &mut ())
+    }
+
+    /// Like [Grid::set], but records every call in `stats`
+    pub(crate) fn set_with_stats(&mut self, pos: Pos, num: Number, stats: &mut impl Stats) -> bool {
+        self.set_with_peers(pos, num, &Pos::get_visible_positions, stats)
+    }
+
+    /// Like [Grid::set], but `peers` returns the positions visible from a
+    /// position instead of [Pos::get_visible_positions]
+    pub(crate) fn set_with_peers<P: AsRef<[Pos]>>(
+        &mut self,
+        pos: Pos,
+        num: Number,
+        peers: &impl Fn(Pos) -> P,
+        stats: &mut impl Stats,
+    ) -> bool {
+        stats.propagate();
+        if stats.is_interrupted() {
+            // the search stops, so the grid doesn't need to be consistent
+            return true;
+        }
+        let visible = peers(pos);
         self[pos].set(num);
-        for visible_pos in visible {
+        for &visible_pos in visible.as_ref() {
             let field = &mut self[visible_pos];
 
             if let Some(solution) = field.solution() {
@@ -72,7 +97,7 @@ impl Grid {
             } else {
                 field.remove(num);
                 if let Some(n) = field.solution() {
-                    let inner_empty = self.set(visible_pos, n);
+                    let inner_empty = self.set_with_peers(visible_pos, n, peers, stats);
                     if inner_empty {
                         return inner_empty;
                     }
@@ -94,7 +119,7 @@ impl Grid {
     /// This function returns `None` if there are no cells with more than one
     /// possible number, meaning that the sudoku is completely solved.
     pub fn best_field_to_guess(&self) -> Option<Pos> {
-        let mut lowest_len = 9;
+        let mut lowest_len = u32::MAX;
         let mut lowest_pos = None;
         for pos in Pos::iterator() {
             let field = self[pos];
@@ -124,6 +149,13 @@ impl Grid {
     }
 }
 
+impl Default for Grid {
+    /// Returns an unconstrained sudoku grid, see [Grid::full]
+    fn default() -> Self {
+        Grid::full()
+    }
+}
+
 impl fmt::Debug for Grid {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (ri, row) in self.fields.iter().enumerate() {
@@ -166,3 +198,18 @@ impl IndexMut<Pos> for Grid {
         }
     }
 }
+
+#[test]
+fn test_eq_and_hash() {
+    let mut rng = rand::rng();
+    let grid = crate::generate(&mut rng);
+
+    let mut set = std::collections::HashSet::new();
+    set.insert(grid.clone());
+    set.insert(Grid::default());
+    set.insert(grid.clone());
+    assert_eq!(set.len(), 2);
+
+    assert_eq!(Grid::default(), Grid::full());
+    assert!(grid < Grid::full());
+}
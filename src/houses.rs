@@ -0,0 +1,206 @@
+use std::sync::LazyLock;
+
+use crate::{Constraint, Eliminations, Field, Grid, Number, Pos, Variant};
+
+/// Additional houses, which must contain every number exactly once, just like
+/// rows, columns and blocks. Used for Sudoku-X and Windoku.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{ExtraHouses, Pos};
+///
+/// let diagonals = ExtraHouses::diagonals();
+/// assert_eq!(diagonals.houses().len(), 2);
+///
+/// let visible = diagonals.visible_positions(Pos::new(0, 0));
+/// assert_eq!(visible.len(), 8);
+/// assert!(visible.contains(&Pos::new(4, 4)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraHouses {
+    houses: Vec<[Pos; 9]>,
+    /// The positions visible from each position through the extra houses,
+    /// indexed by `y * 9 + x`
+    visible: Vec<Vec<Pos>>,
+}
+
+impl ExtraHouses {
+    /// Creates extra houses. Panics if a house contains a position twice.
+    pub fn new(houses: impl IntoIterator<Item = [Pos; 9]>) -> Self {
+        let houses: Vec<[Pos; 9]> = houses.into_iter().collect();
+        let mut visible = vec![Vec::new(); 81];
+        for house in &houses {
+            for (i, &pos) in house.iter().enumerate() {
+                if house[..i].contains(&pos) {
+                    panic!("{pos:?} appears twice in the same house");
+                }
+
+                let visible = &mut visible[pos.y() as usize * 9 + pos.x() as usize];
+                for &other in house {
+                    if other != pos && !visible.contains(&other) {
+                        visible.push(other);
+                    }
+                }
+            }
+        }
+        ExtraHouses { houses, visible }
+    }
+
+    /// The two main diagonals, used for Sudoku-X
+    pub fn diagonals() -> Self {
+        ExtraHouses::new([
+            std::array::from_fn(|i| Pos::new(i as u8, i as u8)),
+            std::array::from_fn(|i| Pos::new(8 - i as u8, i as u8)),
+        ])
+    }
+
+    /// Four 3x3 windows, which are one field apart from each other and from
+    /// the edges of the grid, used for Windoku
+    pub fn windows() -> Self {
+        ExtraHouses::new(
+            [(1, 1), (5, 1), (1, 5), (5, 5)]
+                .map(|(x, y)| std::array::from_fn(|i| Pos::new(x + i as u8 % 3, y + i as u8 / 3))),
+        )
+    }
+
+    /// Returns the extra houses
+    pub fn houses(&self) -> &[[Pos; 9]] {
+        &self.houses
+    }
+
+    /// Returns the positions that are visible from `pos` through the extra
+    /// houses
+    pub fn visible_positions(&self, pos: Pos) -> &[Pos] {
+        &self.visible[pos.y() as usize * 9 + pos.x() as usize]
+    }
+}
+
+impl Constraint for ExtraHouses {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        for &other in self.visible_positions(pos) {
+            eliminations.remove(other, num);
+        }
+    }
+
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        for house in &self.houses {
+            hidden_singles(grid, house, eliminations);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        self.houses.iter().all(|house| {
+            let numbers: Field = house
+                .iter()
+                .filter_map(|&pos| grid[pos].solution())
+                .collect();
+            numbers == Field::full()
+        })
+    }
+}
+
+/// Returns the rows, columns and blocks of the classic rules
+pub(crate) fn classic_houses() -> &'static [[Pos; 9]; 27] {
+    static HOUSES: LazyLock<[[Pos; 9]; 27]> = LazyLock::new(|| {
+        std::array::from_fn(|i| {
+            let i = i as u8;
+            std::array::from_fn(|j| {
+                let j = j as u8;
+                match i / 9 {
+                    0 => Pos::new(j, i),
+                    1 => Pos::new(i % 9, j),
+                    _ => Pos::new(i % 3 * 3 + j % 3, i % 9 / 3 * 3 + j / 3),
+                }
+            })
+        })
+    });
+    &HOUSES
+}
+
+/// Places numbers that fit only in one field of a house, and reports a
+/// contradiction if a number doesn't fit anywhere
+pub(crate) fn hidden_singles(grid: &Grid, house: &[Pos; 9], eliminations: &mut Eliminations) {
+    for num in Field::full().possibilities() {
+        let mut fields = house.iter().filter(|&&pos| grid[pos].contains(num));
+        match (fields.next(), fields.next()) {
+            (None, _) => eliminations.contradiction(),
+            (Some(&pos), None) => eliminations.restrict(pos, num.into()),
+            _ => {}
+        }
+    }
+}
+
+impl Variant {
+    /// Sudoku-X: The two main diagonals must contain every number once
+    pub fn sudoku_x() -> Self {
+        Variant::new().with(ExtraHouses::diagonals())
+    }
+
+    /// Windoku: Four additional 3x3 windows must contain every number once,
+    /// see [ExtraHouses::windows]
+    pub fn windoku() -> Self {
+        Variant::new().with(ExtraHouses::windows())
+    }
+}
+
+#[test]
+fn test_extra_houses() {
+    use crate::variant::{check_generated, eliminated};
+
+    check_generated(&Variant::sudoku_x(), 1);
+    check_generated(&Variant::windoku(), 2);
+
+    // a number is removed from the houses of its position only
+    let full = Grid::full();
+    let diagonals = ExtraHouses::diagonals();
+    let grid = eliminated(&full, |e| {
+        diagonals.on_placement(&full, Pos::new(0, 0), Number::N5, e)
+    })
+    .unwrap();
+    assert!(grid[Pos::new(0, 0)].contains(Number::N5));
+    assert!(!grid[Pos::new(4, 4)].contains(Number::N5));
+    assert!(!grid[Pos::new(8, 8)].contains(Number::N5));
+    assert!(grid[Pos::new(8, 0)].contains(Number::N5));
+    let windows = ExtraHouses::windows();
+    let grid = eliminated(&full, |e| {
+        windows.on_placement(&full, Pos::new(0, 0), Number::N5, e)
+    });
+    assert_eq!(grid, Some(full.clone()));
+
+    // 7 fits only at (2, 2) on the diagonal, and nowhere on the other one
+    let mut grid = Grid::full();
+    for i in (0..9).filter(|&i| i != 2) {
+        grid[Pos::new(i, i)] -= Number::N7.into();
+    }
+    let grid = eliminated(&grid, |e| diagonals.eliminations(&grid, e)).unwrap();
+    assert_eq!(grid[Pos::new(2, 2)], Field::new(7));
+    let mut grid = Grid::full();
+    for i in 0..9 {
+        grid[Pos::new(8 - i, i)] -= Number::N7.into();
+    }
+    assert_eq!(
+        eliminated(&grid, |e| diagonals.eliminations(&grid, e)),
+        None
+    );
+
+    // a classic sudoku grid, where the diagonals contain duplicates
+    let grid = Grid::new([
+        crate::row![1 2 3  4 5 6  7 8 9],
+        crate::row![4 5 6  7 8 9  1 2 3],
+        crate::row![7 8 9  1 2 3  4 5 6],
+        crate::row![2 3 4  5 6 7  8 9 1],
+        crate::row![5 6 7  8 9 1  2 3 4],
+        crate::row![8 9 1  2 3 4  5 6 7],
+        crate::row![3 4 5  6 7 8  9 1 2],
+        crate::row![6 7 8  9 1 2  3 4 5],
+        crate::row![9 1 2  3 4 5  6 7 8],
+    ]);
+    assert!(!diagonals.is_valid(&grid));
+    assert_eq!(
+        crate::count_solutions_with_variant(&grid, &Variant::sudoku_x(), 1),
+        crate::SolutionCount::Exact(0)
+    );
+    // unsolved fields don't count as any number
+    assert!(!diagonals.is_valid(&full));
+}
@@ -0,0 +1,337 @@
+use rand::seq::{IndexedRandom, SliceRandom};
+
+use crate::solve::for_each_solution;
+use crate::{Constraint, Eliminations, Field, Grid, Number, Pos, SolveOptions, Variant};
+
+/// A cage of a killer sudoku: The numbers in the cage must add up to the sum,
+/// and must not repeat.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cage {
+    positions: Vec<Pos>,
+    sum: u8,
+    /// All sets of distinct numbers that add up to the sum
+    combinations: Vec<Field>,
+}
+
+impl Cage {
+    /// Creates a cage. Panics if it is empty, contains more than 9 fields, or
+    /// contains a field twice.
+    pub fn new(positions: impl IntoIterator<Item = Pos>, sum: u8) -> Self {
+        let positions: Vec<Pos> = positions.into_iter().collect();
+        if positions.is_empty() || positions.len() > 9 {
+            panic!("A cage must contain 1 to 9 fields");
+        }
+        for (i, pos) in positions.iter().enumerate() {
+            if positions[..i].contains(pos) {
+                panic!("{pos:?} appears twice in the same cage");
+            }
+        }
+
+        let combinations = (0..1 << 9)
+            .map(Field::from_bits)
+            .filter(|field| field.len() as usize == positions.len() && digit_sum(*field) == sum)
+            .collect();
+        Cage {
+            positions,
+            sum,
+            combinations,
+        }
+    }
+
+    /// Returns the fields of the cage
+    pub fn positions(&self) -> &[Pos] {
+        &self.positions
+    }
+
+    /// Returns the sum of the numbers in the cage
+    pub fn sum(&self) -> u8 {
+        self.sum
+    }
+}
+
+fn digit_sum(field: Field) -> u8 {
+    field.possibilities().map(Number::as_u8).sum()
+}
+
+/// The cages of a killer sudoku.
+///
+/// To rule out possibilities, all combinations of distinct numbers with the
+/// right sum are considered for each cage. A combination is ruled out if it
+/// doesn't contain a solved number, if one of its numbers isn't possible in
+/// any field, or if a field can't contain any of its numbers.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Grid, Killer, SolutionCount, Variant, count_solutions_with_variant};
+///
+/// let mut rng = rand::rng();
+/// let solution = sudoku::generate(&mut rng);
+/// let killer = Killer::generate(&solution, &mut rng);
+///
+/// let variant = Variant::new().with(killer);
+/// let count = count_solutions_with_variant(&Grid::full(), &variant, 2);
+/// assert_eq!(count, SolutionCount::Exact(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Killer {
+    cages: Vec<Cage>,
+    /// The index of the cage containing each position, indexed by
+    /// `y * 9 + x`
+    cage_of: Vec<Option<usize>>,
+}
+
+impl Killer {
+    /// Creates a killer sudoku. Panics if a field is in more than one cage.
+    pub fn new(cages: impl IntoIterator<Item = Cage>) -> Self {
+        let cages: Vec<Cage> = cages.into_iter().collect();
+        let mut cage_of = vec![None; 81];
+        for (i, cage) in cages.iter().enumerate() {
+            for &pos in &cage.positions {
+                let cage_of = &mut cage_of[index(pos)];
+                if cage_of.is_some() {
+                    panic!("{pos:?} is in more than one cage");
+                }
+                *cage_of = Some(i);
+            }
+        }
+        Killer { cages, cage_of }
+    }
+
+    /// Returns the cages
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /// Partitions a solved grid into connected cages, so that the killer
+    /// sudoku without givens has `solution` as its only solution.
+    ///
+    /// The grid is first partitioned into random cages of 2 to 5 fields.
+    /// While there is another solution, a cage where the solutions differ is
+    /// split into smaller cages. Afterwards, cages with a single field, which
+    /// are just givens in disguise, are merged into neighboring cages where
+    /// possible.
+    pub fn generate(solution: &Grid, rng: &mut impl rand::Rng) -> Self {
+        let mut cages: Vec<Vec<Pos>> = Vec::new();
+        let mut free: Vec<Pos> = Pos::iterator().collect();
+        grow_cages(solution, &mut free, &mut cages, 5, rng);
+
+        loop {
+            let killer = Killer::from_solution(solution, &cages);
+            let i = match killer.other_solution(solution) {
+                Uniqueness::Unique => break,
+                Uniqueness::Other(other) => {
+                    let differing: Vec<Pos> = Pos::iterator()
+                        .filter(|&p| other[p] != solution[p])
+                        .collect();
+                    let &pos = differing.choose(rng).unwrap();
+                    killer.cage_of[index(pos)].unwrap()
+                }
+                Uniqueness::Unknown => (0..cages.len()).max_by_key(|&i| cages[i].len()).unwrap(),
+            };
+
+            let mut free = cages.swap_remove(i);
+            let max_size = free.len().saturating_sub(1).max(1);
+            grow_cages(solution, &mut free, &mut cages, max_size, rng);
+        }
+
+        let mut i = 0;
+        while i < cages.len() {
+            if cages[i].len() == 1 && merge_single(solution, &mut cages, i, rng) {
+                continue;
+            }
+            i += 1;
+        }
+        Killer::from_solution(solution, &cages)
+    }
+
+    /// Creates cages with the sums from `solution`
+    fn from_solution(solution: &Grid, cages: &[Vec<Pos>]) -> Self {
+        Killer::new(cages.iter().map(|positions| {
+            let sum = positions.iter().filter_map(|&p| solution[p].solution());
+            Cage::new(positions.iter().copied(), sum.map(Number::as_u8).sum())
+        }))
+    }
+
+    /// Looks for a solution other than `solution`. The search is limited,
+    /// because proving uniqueness takes very long for some partitions.
+    fn other_solution(&self, solution: &Grid) -> Uniqueness {
+        let variant = Variant::new().with(self.clone());
+        let options = SolveOptions::default().with_max_guesses(10_000);
+        let mut other = None;
+        let status = for_each_solution(&Grid::full(), Some(&variant), &options, |grid| {
+            if grid != solution {
+                other = Some(grid.clone());
+            }
+            other.is_none()
+        });
+
+        match other {
+            Some(other) => Uniqueness::Other(other),
+            None if status.is_finished() => Uniqueness::Unique,
+            None => Uniqueness::Unknown,
+        }
+    }
+}
+
+enum Uniqueness {
+    Unique,
+    Other(Grid),
+    Unknown,
+}
+
+/// Tries to merge the cage `i` with a single field into a neighboring cage,
+/// while keeping the solution unique. Returns whether it was merged.
+fn merge_single(
+    solution: &Grid,
+    cages: &mut Vec<Vec<Pos>>,
+    i: usize,
+    rng: &mut impl rand::Rng,
+) -> bool {
+    let pos = cages[i][0];
+    let mut neighbors: Vec<usize> = (0..cages.len())
+        .filter(|&j| j != i && cages[j].iter().any(|&p| is_adjacent(p, pos)))
+        .filter(|&j| cages[j].iter().all(|&p| solution[p] != solution[pos]))
+        .collect();
+    neighbors.shuffle(rng);
+
+    for j in neighbors {
+        let mut merged = cages.clone();
+        merged[j].push(pos);
+        merged.swap_remove(i);
+        let killer = Killer::from_solution(solution, &merged);
+        if let Uniqueness::Unique = killer.other_solution(solution) {
+            *cages = merged;
+            return true;
+        }
+    }
+    false
+}
+
+fn is_adjacent(a: Pos, b: Pos) -> bool {
+    a.x().abs_diff(b.x()) + a.y().abs_diff(b.y()) == 1
+}
+
+/// Partitions the `free` positions into connected cages of up to `max_size`
+/// fields without repeated numbers, and adds them to `cages`
+fn grow_cages(
+    solution: &Grid,
+    free: &mut Vec<Pos>,
+    cages: &mut Vec<Vec<Pos>>,
+    max_size: usize,
+    rng: &mut impl rand::Rng,
+) {
+    free.shuffle(rng);
+    while let Some(start) = free.pop() {
+        let size = rng.random_range(max_size.min(2)..=max_size);
+        let mut cage = vec![start];
+        let mut numbers = solution[start];
+        while cage.len() < size {
+            let neighbors: Vec<Pos> = free
+                .iter()
+                .copied()
+                .filter(|&p| (solution[p] & numbers).is_empty())
+                .filter(|&p| cage.iter().any(|&c| is_adjacent(c, p)))
+                .collect();
+            let Some(&next) = neighbors.choose(rng) else {
+                break;
+            };
+            free.retain(|&p| p != next);
+            cage.push(next);
+            numbers |= solution[next];
+        }
+        cages.push(cage);
+    }
+}
+
+fn index(pos: Pos) -> usize {
+    pos.y() as usize * 9 + pos.x() as usize
+}
+
+impl Constraint for Killer {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        if let Some(i) = self.cage_of[index(pos)] {
+            for &other in &self.cages[i].positions {
+                if other != pos {
+                    eliminations.remove(other, num);
+                }
+            }
+        }
+    }
+
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        for cage in &self.cages {
+            let mut solved = Field::empty();
+            let mut possible = Field::empty();
+            for &pos in &cage.positions {
+                possible |= grid[pos];
+                if let Some(num) = grid[pos].solution() {
+                    solved.insert(num);
+                }
+            }
+
+            let mut allowed = Field::empty();
+            for &combination in &cage.combinations {
+                if combination & solved == solved
+                    && combination & possible == combination
+                    && cage
+                        .positions
+                        .iter()
+                        .all(|&p| !(grid[p] & combination).is_empty())
+                {
+                    allowed |= combination;
+                }
+            }
+
+            if allowed.is_empty() {
+                eliminations.contradiction();
+                return;
+            }
+            for &pos in &cage.positions {
+                eliminations.restrict(pos, allowed);
+            }
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        self.cages.iter().all(|cage| {
+            let numbers: Field = cage
+                .positions
+                .iter()
+                .filter_map(|&p| grid[p].solution())
+                .collect();
+            numbers.len() as usize == cage.positions.len() && digit_sum(numbers) == cage.sum
+        })
+    }
+}
+
+impl Variant {
+    /// Killer sudoku: The classic rules, plus cages with sums
+    pub fn killer(cages: impl IntoIterator<Item = Cage>) -> Self {
+        Variant::new().with(Killer::new(cages))
+    }
+}
+
+#[test]
+fn test_killer() {
+    let cage = Cage::new([Pos::new(0, 0), Pos::new(1, 0)], 4);
+    assert_eq!(cage.combinations, [Field::from_bits(0b101)]);
+
+    let variant = Variant::killer([cage]);
+    let grid = variant.new_grid(Grid::full().fields);
+    assert_eq!(grid[Pos::new(0, 0)], Field::from_bits(0b101));
+
+    let mut rng = <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(0);
+    let solution = crate::generate_from_seed(0);
+    let killer = Killer::generate(&solution, &mut rng);
+    assert!(killer.is_valid(&solution));
+    let variant = Variant::new().with(killer);
+    let mut acc = Vec::new();
+    crate::solve_with_variant(
+        &Grid::full(),
+        &variant,
+        &mut acc,
+        SolveOptions::with_max_solutions(2),
+    );
+    assert_eq!(acc, [solution]);
+}
@@ -1,16 +1,57 @@
+mod bytes;
+mod canonical;
+mod chess;
+mod dots;
+mod edge;
+mod error;
 mod field;
+pub mod formats;
 mod generate;
 mod grid;
+mod houses;
+mod killer;
+mod lines;
 mod number;
+mod outside;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pencil_marks;
 mod pos;
+mod regions;
+mod restrictions;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod sized;
 mod solve;
+mod stream;
+mod transform;
+mod variant;
 
+pub use chess::{AntiKing, AntiKnight, AntiQueen};
+pub use dots::{GreaterThan, Kropki, NonConsecutive, XV};
+pub use edge::Edge;
+pub use error::{ParseError, ParseErrorKind, RegionError};
 pub use field::Field;
-pub use generate::generate;
+pub use generate::{generate, generate_from_seed, generate_with_variant};
 pub use grid::Grid;
+pub use houses::ExtraHouses;
+pub use killer::{Cage, Killer};
+pub use lines::{Arrow, GermanWhisper, Palindrome, Renban, Thermometer};
 pub use number::Number;
+pub use outside::{Diagonal, LittleKiller, Sandwich, Side, Skyscraper, XSum};
+#[cfg(feature = "rayon")]
+pub use parallel::{par_count_solutions, par_generate};
 pub use pos::Pos;
-pub use solve::{SolveOptions, solve};
+pub use regions::Regions;
+pub use restrictions::Restrictions;
+pub use sized::{Geometry, SizedGrid, generate_sized};
+pub use solve::{
+    CancelToken, SolutionCount, SolveOptions, SolveStats, SolveStatus, count_solutions,
+    count_solutions_with_variant, solutions, solve, solve_with_stats, solve_with_variant,
+};
+pub use stream::{PuzzleReader, PuzzleWriter};
+pub use transform::Transform;
+pub use variant::{Constraint, Eliminations, Variant};
 
 #[macro_export]
 macro_rules! row {
@@ -21,6 +62,6 @@ macro_rules! row {
         $crate::Field::full()
     };
     ($($t:tt)*) => {
-        [$( row!(field $t) ),*]
+        [$( $crate::row!(field $t) ),*]
     }
 }
@@ -0,0 +1,432 @@
+use crate::{Constraint, Eliminations, Field, Grid, Number, Pos};
+
+/// Numbers on a thermometer must strictly increase, starting at the bulb.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Field, Pos, Thermometer, Variant};
+///
+/// let thermometer = Thermometer::new((0..4).map(|x| Pos::new(x, 0)));
+/// let grid = Variant::new().with(thermometer).new_grid([[Field::full(); 9]; 9]);
+///
+/// assert_eq!(grid[Pos::new(0, 0)], Field::from_bits(0b000_111_111));
+/// assert_eq!(grid[Pos::new(3, 0)], Field::from_bits(0b111_111_000));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Thermometer(Vec<Pos>);
+
+/// The number in the circle must equal the sum of the numbers along the
+/// arrow. Numbers may repeat along the arrow, if the classic rules allow it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Arrow {
+    circle: Pos,
+    arrow: Vec<Pos>,
+}
+
+/// Adjacent numbers on a German whispers line must differ by at least 5.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GermanWhisper(Vec<Pos>);
+
+/// The numbers on a renban line must be a set of consecutive numbers in any
+/// order, without repetitions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Renban(Vec<Pos>);
+
+/// A palindrome line must read the same in both directions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Palindrome(Vec<Pos>);
+
+/// Collects the positions of a line. Panics if the line is empty, or a
+/// position appears twice.
+fn line(positions: impl IntoIterator<Item = Pos>) -> Vec<Pos> {
+    let positions: Vec<Pos> = positions.into_iter().collect();
+    if positions.is_empty() {
+        panic!("A line must have at least one position");
+    }
+    for (i, pos) in positions.iter().enumerate() {
+        if positions[..i].contains(pos) {
+            panic!("{pos:?} appears twice in the same line");
+        }
+    }
+    positions
+}
+
+fn solutions(grid: &Grid, line: &[Pos]) -> Option<Vec<u8>> {
+    line.iter()
+        .map(|&pos| grid[pos].solution().map(Number::as_u8))
+        .collect()
+}
+
+/// Returns the smallest number in the field, or 10 if it is empty
+fn min(field: Field) -> u8 {
+    field.possibilities().next().map_or(10, Number::as_u8)
+}
+
+/// Returns the greatest number in the field, or 0 if it is empty
+fn max(field: Field) -> u8 {
+    field.possibilities().last().map_or(0, Number::as_u8)
+}
+
+/// Returns all numbers in the range, e.g. `numbers(3, 5)` contains 3, 4 and 5
+fn numbers(from: u8, to: u8) -> Field {
+    (from.max(1)..=to.min(9)).map(Number::new).collect()
+}
+
+impl Thermometer {
+    /// Creates a thermometer, starting at the bulb. Panics if the line is
+    /// empty, or a position appears twice.
+    pub fn new(positions: impl IntoIterator<Item = Pos>) -> Self {
+        Thermometer(line(positions))
+    }
+
+    /// Returns the positions, starting at the bulb
+    pub fn positions(&self) -> &[Pos] {
+        &self.0
+    }
+}
+
+impl Constraint for Thermometer {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        let mut lowest = 0;
+        for &pos in &self.0 {
+            let allowed = grid[pos] & numbers(lowest + 1, 9);
+            eliminations.restrict(pos, allowed);
+            lowest = min(allowed);
+        }
+        let mut highest: u8 = 10;
+        for &pos in self.0.iter().rev() {
+            let allowed = grid[pos] & numbers(1, highest.saturating_sub(1));
+            eliminations.restrict(pos, allowed);
+            highest = max(allowed);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        solutions(grid, &self.0).is_some_and(|n| n.windows(2).all(|w| w[0] < w[1]))
+    }
+}
+
+impl Arrow {
+    /// Creates an arrow. Panics if the arrow is empty, or a position appears
+    /// twice.
+    pub fn new(circle: Pos, arrow: impl IntoIterator<Item = Pos>) -> Self {
+        let arrow = line(arrow);
+        if arrow.contains(&circle) {
+            panic!("{circle:?} is both the circle and on the arrow");
+        }
+        Arrow { circle, arrow }
+    }
+
+    /// Returns the position of the circle
+    pub fn circle(&self) -> Pos {
+        self.circle
+    }
+
+    /// Returns the positions along the arrow, starting next to the circle
+    pub fn arrow(&self) -> &[Pos] {
+        &self.arrow
+    }
+}
+
+impl Constraint for Arrow {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        if self.arrow.iter().any(|&p| grid[p].is_empty()) {
+            eliminations.contradiction();
+            return;
+        }
+        // long arrows can exceed the range of u8
+        let min_sum: u16 = self.arrow.iter().map(|&p| min(grid[p]) as u16).sum();
+        let max_sum: u16 = self.arrow.iter().map(|&p| max(grid[p]) as u16).sum();
+        let circle = grid[self.circle] & numbers(min_sum.min(10) as u8, max_sum.min(9) as u8);
+        if circle.is_empty() {
+            eliminations.contradiction();
+            return;
+        }
+        eliminations.restrict(self.circle, circle);
+
+        for &pos in &self.arrow {
+            let field = grid[pos];
+            // the sum of the other numbers on the arrow
+            let others_min = min_sum - min(field) as u16;
+            let others_max = max_sum - max(field) as u16;
+            let from = (min(circle) as u16).saturating_sub(others_max);
+            let to = (max(circle) as u16).saturating_sub(others_min);
+            // both are at most 9, since the circle contains a number
+            eliminations.restrict(pos, field & numbers(from as u8, to as u8));
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        let circle = grid[self.circle].solution().map(|n| n.as_u8() as u16);
+        let arrow = solutions(grid, &self.arrow).map(|n| n.iter().map(|&n| n as u16).sum());
+        circle.is_some() && circle == arrow
+    }
+}
+
+impl GermanWhisper {
+    /// Creates a German whispers line. Panics if the line is empty, or a
+    /// position appears twice.
+    pub fn new(positions: impl IntoIterator<Item = Pos>) -> Self {
+        GermanWhisper(line(positions))
+    }
+
+    /// Returns the positions along the line
+    pub fn positions(&self) -> &[Pos] {
+        &self.0
+    }
+}
+
+/// Returns the numbers that differ by at least 5 from one of the numbers in
+/// `field`
+fn whisper_neighbors(field: Field) -> Field {
+    let mut result = Field::empty();
+    if min(field) <= 4 {
+        result |= numbers(min(field) + 5, 9);
+    }
+    if max(field) >= 6 {
+        result |= numbers(1, max(field) - 5);
+    }
+    result
+}
+
+impl Constraint for GermanWhisper {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        for (i, &pos) in self.0.iter().enumerate() {
+            // 5 can never be on a whispers line
+            let mut allowed = grid[pos] & !Field::new(5);
+            if i > 0 {
+                allowed &= whisper_neighbors(grid[self.0[i - 1]]);
+            }
+            if let Some(&next) = self.0.get(i + 1) {
+                allowed &= whisper_neighbors(grid[next]);
+            }
+            eliminations.restrict(pos, allowed);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        solutions(grid, &self.0).is_some_and(|n| n.windows(2).all(|w| w[0].abs_diff(w[1]) >= 5))
+    }
+}
+
+impl Renban {
+    /// Creates a renban line. Panics if the line is empty or has more than 9
+    /// positions, or if a position appears twice.
+    pub fn new(positions: impl IntoIterator<Item = Pos>) -> Self {
+        let positions = line(positions);
+        if positions.len() > 9 {
+            panic!("A renban line can't have more than 9 positions");
+        }
+        Renban(positions)
+    }
+
+    /// Returns the positions along the line
+    pub fn positions(&self) -> &[Pos] {
+        &self.0
+    }
+}
+
+impl Constraint for Renban {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        if self.0.contains(&pos) {
+            for &other in self.0.iter().filter(|&&other| other != pos) {
+                eliminations.remove(other, num);
+            }
+        }
+    }
+
+    /// Considers every set of consecutive numbers, and keeps the ones where
+    /// every number fits somewhere, and every field can contain one of them
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        let len = self.0.len() as u8;
+        let mut possible = Field::empty();
+        let mut solved = Field::empty();
+        for &pos in &self.0 {
+            possible |= grid[pos];
+            solved |= grid[pos].solution().map_or(Field::empty(), Field::from);
+        }
+
+        let mut allowed = Field::empty();
+        for start in 1..=10 - len {
+            let set = numbers(start, start + len - 1);
+            if set & possible == set
+                && set & solved == solved
+                && self.0.iter().all(|&p| !(grid[p] & set).is_empty())
+            {
+                allowed |= set;
+            }
+        }
+        if allowed.is_empty() {
+            eliminations.contradiction();
+        }
+        for &pos in &self.0 {
+            eliminations.restrict(pos, allowed);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        solutions(grid, &self.0).is_some_and(|mut n| {
+            n.sort_unstable();
+            n.windows(2).all(|w| w[0] + 1 == w[1])
+        })
+    }
+}
+
+impl Palindrome {
+    /// Creates a palindrome line. Panics if the line is empty, or a position
+    /// appears twice.
+    pub fn new(positions: impl IntoIterator<Item = Pos>) -> Self {
+        Palindrome(line(positions))
+    }
+
+    /// Returns the positions along the line
+    pub fn positions(&self) -> &[Pos] {
+        &self.0
+    }
+
+    /// Returns the pairs of positions that must contain the same number
+    fn pairs(&self) -> impl Iterator<Item = (Pos, Pos)> + '_ {
+        self.0
+            .iter()
+            .copied()
+            .zip(self.0.iter().rev().copied())
+            .take(self.0.len() / 2)
+    }
+}
+
+impl Constraint for Palindrome {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        for (a, b) in self.pairs() {
+            let allowed = grid[a] & grid[b];
+            eliminations.restrict(a, allowed);
+            eliminations.restrict(b, allowed);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        self.pairs()
+            .all(|(a, b)| grid[a].solution().is_some() && grid[a] == grid[b])
+    }
+}
+
+#[test]
+fn test_lines() {
+    use crate::Variant;
+    use crate::variant::check_generated;
+
+    let row = |y: u8, xs: std::ops::Range<u8>| xs.map(move |x| Pos::new(x, y));
+    let variant = Variant::new()
+        .with(Thermometer::new(row(0, 0..3)))
+        .with(Arrow::new(Pos::new(0, 2), row(2, 1..4)))
+        .with(GermanWhisper::new(row(4, 0..5)))
+        .with(Renban::new(row(6, 2..6)))
+        .with(Palindrome::new([
+            Pos::new(6, 0),
+            Pos::new(7, 1),
+            Pos::new(8, 2),
+            Pos::new(8, 3),
+            Pos::new(7, 4),
+        ]));
+
+    let grid = variant.new_grid([[Field::full(); 9]; 9]);
+    assert_eq!(grid[Pos::new(0, 2)], Field::from_bits(0b111_111_100));
+    assert_eq!(grid[Pos::new(2, 4)], Field::from_bits(0b111_101_111));
+    check_generated(&variant, 6);
+}
+
+#[test]
+fn test_line_constraints() {
+    use crate::variant::eliminated;
+
+    // a grid where the fields of the first row are set to `nums`, with 0 for
+    // an unsolved field
+    let first_row = |nums: &[u8]| {
+        let mut grid = Grid::full();
+        for (x, &n) in nums.iter().enumerate() {
+            if n > 0 {
+                grid[Pos::new(x as u8, 0)] = Field::new(n);
+            }
+        }
+        grid
+    };
+    let row = |xs: std::ops::Range<u8>| xs.map(|x| Pos::new(x, 0));
+    let field = |grid: &Grid, x: u8| grid[Pos::new(x, 0)];
+
+    let thermometer = Thermometer::new(row(0..3));
+    let grid = first_row(&[5]);
+    let grid = eliminated(&grid, |e| thermometer.eliminations(&grid, e)).unwrap();
+    assert_eq!(field(&grid, 0), Field::new(5));
+    assert_eq!(field(&grid, 1), Field::from_bits(0b011_100_000));
+    assert_eq!(field(&grid, 2), Field::from_bits(0b111_000_000));
+    let grid = first_row(&[8]);
+    let grid = eliminated(&grid, |e| thermometer.eliminations(&grid, e)).unwrap();
+    assert!(field(&grid, 2).is_empty());
+    assert!(thermometer.is_valid(&first_row(&[1, 2, 9])));
+    assert!(!thermometer.is_valid(&first_row(&[1, 3, 3])));
+    assert!(!thermometer.is_valid(&first_row(&[1, 0, 3])));
+
+    let arrow = Arrow::new(Pos::new(0, 0), row(1..3));
+    let grid = Grid::full();
+    let grid = eliminated(&grid, |e| arrow.eliminations(&grid, e)).unwrap();
+    assert_eq!(field(&grid, 0), Field::from_bits(0b111_111_110));
+    assert_eq!(field(&grid, 1), Field::from_bits(0b011_111_111));
+    let grid = first_row(&[3]);
+    let grid = eliminated(&grid, |e| arrow.eliminations(&grid, e)).unwrap();
+    assert_eq!(field(&grid, 2), Field::from_bits(0b000_000_011));
+    assert!(arrow.is_valid(&first_row(&[5, 2, 3])));
+    assert!(arrow.is_valid(&first_row(&[4, 2, 2])));
+    assert!(!arrow.is_valid(&first_row(&[5, 2, 2])));
+    assert!(!arrow.is_valid(&first_row(&[5, 5, 0])));
+    let long = Arrow::new(Pos::new(0, 0), Pos::iterator().skip(1).take(30));
+    let grid = Grid::full();
+    assert_eq!(eliminated(&grid, |e| long.eliminations(&grid, e)), None);
+    assert!(!long.is_valid(&crate::generate_from_seed(0)));
+
+    let whisper = GermanWhisper::new(row(0..3));
+    let grid = first_row(&[0, 3]);
+    let grid = eliminated(&grid, |e| whisper.eliminations(&grid, e)).unwrap();
+    assert_eq!(field(&grid, 0), Field::from_bits(0b110_000_000));
+    assert_eq!(field(&grid, 2), Field::from_bits(0b110_000_000));
+    let grid = first_row(&[1, 0, 9]);
+    let grid = eliminated(&grid, |e| whisper.eliminations(&grid, e)).unwrap();
+    assert!(field(&grid, 1).is_empty());
+    assert!(whisper.is_valid(&first_row(&[1, 6, 1])));
+    assert!(!whisper.is_valid(&first_row(&[1, 6, 2])));
+    assert!(!whisper.is_valid(&first_row(&[1, 6])));
+
+    let renban = Renban::new(row(0..3));
+    let grid = first_row(&[9]);
+    let grid = eliminated(&grid, |e| renban.eliminations(&grid, e)).unwrap();
+    assert_eq!(field(&grid, 1), Field::from_bits(0b111_000_000));
+    let grid = first_row(&[1, 9]);
+    assert_eq!(eliminated(&grid, |e| renban.eliminations(&grid, e)), None);
+    let full = Grid::full();
+    let grid = eliminated(&full, |e| {
+        renban.on_placement(&full, Pos::new(0, 0), Number::N4, e)
+    })
+    .unwrap();
+    assert!(field(&grid, 0).contains(Number::N4));
+    assert!(!field(&grid, 2).contains(Number::N4));
+    assert!(renban.is_valid(&first_row(&[3, 1, 2])));
+    assert!(!renban.is_valid(&first_row(&[1, 2, 4])));
+    assert!(!renban.is_valid(&first_row(&[1, 2, 0])));
+
+    let palindrome = Palindrome::new(row(0..3));
+    let mut grid = Grid::full();
+    grid[Pos::new(0, 0)] = Field::from_bits(0b011);
+    grid[Pos::new(2, 0)] = Field::from_bits(0b110);
+    let grid = eliminated(&grid, |e| palindrome.eliminations(&grid, e)).unwrap();
+    assert_eq!(field(&grid, 0), Field::new(2));
+    assert_eq!(field(&grid, 2), Field::new(2));
+    assert_eq!(field(&grid, 1), Field::full());
+    assert!(palindrome.is_valid(&first_row(&[4, 0, 4])));
+    assert!(!palindrome.is_valid(&first_row(&[4, 0, 5])));
+    assert!(!palindrome.is_valid(&first_row(&[0, 4, 0])));
+}
+
+#[test]
+#[should_panic(expected = "at least one position")]
+fn test_empty_line() {
+    Thermometer::new([]);
+}
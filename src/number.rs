@@ -5,7 +5,7 @@ use std::{fmt, num::NonZeroU16};
 /// This is represented as a power of 2, to make conversions between
 /// [Field](super::Field) and Number easier.
 #[repr(transparent)]
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Number(NonZeroU16);
 
 impl Number {
@@ -0,0 +1,450 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use crate::{Constraint, Eliminations, Field, Grid, Number, Pos};
+
+/// A side of the grid, where the clues of a row or column are placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl Side {
+    /// Returns the positions of a row (for [Side::Left] and [Side::Right]) or
+    /// column (for [Side::Top] and [Side::Bottom]), in the order they are
+    /// seen from this side. Panics if `index` is greater than 8.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use sudoku::{Pos, Side};
+    ///
+    /// let line = Side::Right.line(2);
+    /// assert_eq!(line[0], Pos::new(8, 2));
+    /// assert_eq!(line[8], Pos::new(0, 2));
+    /// ```
+    pub fn line(self, index: u8) -> [Pos; 9] {
+        std::array::from_fn(|i| {
+            let i = i as u8;
+            match self {
+                Side::Top => Pos::new(index, i),
+                Side::Right => Pos::new(8 - i, index),
+                Side::Bottom => Pos::new(index, 8 - i),
+                Side::Left => Pos::new(i, index),
+            }
+        })
+    }
+}
+
+/// A diagonal line from the border of the grid to the opposite border, along
+/// which a little killer clue is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Diagonal {
+    start: Pos,
+    dx: i8,
+    dy: i8,
+}
+
+impl Diagonal {
+    /// Creates a diagonal entering the grid at `start`, and continuing in
+    /// the direction (`dx`, `dy`). Panics if `dx` or `dy` isn't 1 or -1, or
+    /// if `start` isn't on the border where the diagonal enters the grid.
+    pub fn new(start: Pos, dx: i8, dy: i8) -> Self {
+        if dx.abs() != 1 || dy.abs() != 1 {
+            panic!("({dx}, {dy}) is not a diagonal direction");
+        }
+        if offset(start, -dx, -dy).is_some() {
+            panic!("The diagonal doesn't enter the grid at {start:?}");
+        }
+        Diagonal { start, dx, dy }
+    }
+
+    /// Returns the positions, starting where the diagonal enters the grid
+    pub fn positions(self) -> impl Iterator<Item = Pos> {
+        std::iter::successors(Some(self.start), move |&pos| offset(pos, self.dx, self.dy))
+    }
+}
+
+fn offset(pos: Pos, dx: i8, dy: i8) -> Option<Pos> {
+    let (x, y) = (pos.x() as i8 + dx, pos.y() as i8 + dy);
+    ((0..9).contains(&x) && (0..9).contains(&y)).then(|| Pos::new(x as u8, y as u8))
+}
+
+/// The sum of the numbers between the 1 and the 9 in a row or column.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Field, Pos, Sandwich, Side, Variant};
+///
+/// let sandwich = Sandwich::new(Side::Left, 0, 35);
+/// let grid = Variant::new().with(sandwich).new_grid([[Field::full(); 9]; 9]);
+///
+/// // all other numbers are between the 1 and the 9
+/// assert_eq!(grid[Pos::new(0, 0)], Field::from_bits(0b100_000_001));
+/// assert_eq!(grid[Pos::new(8, 0)], Field::from_bits(0b100_000_001));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Sandwich {
+    side: Side,
+    index: u8,
+    sum: u8,
+}
+
+/// The number of skyscrapers visible from outside the grid, where every
+/// number is the height of a skyscraper, and hides all smaller skyscrapers
+/// behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Skyscraper {
+    side: Side,
+    index: u8,
+    count: u8,
+}
+
+/// The sum of the first X numbers seen from outside the grid, where X is the
+/// first number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct XSum {
+    side: Side,
+    index: u8,
+    sum: u8,
+}
+
+/// The sum of the numbers along a diagonal. Unlike in a killer cage, numbers
+/// may repeat if the classic rules allow it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LittleKiller {
+    diagonal: Diagonal,
+    sum: u8,
+}
+
+/// Rules out possibilities that aren't part of any sequence of numbers along
+/// `line` that is accepted by a finite automaton. Starting at `start`, the
+/// automaton reads one number after another with `step`, which returns the
+/// next state or `None` to reject the sequence. Finally, `accept` decides
+/// whether a sequence is accepted.
+///
+/// Since the states are merged, this is much faster than trying every
+/// permutation of the possibilities.
+fn prune<S: Copy + Eq + Hash>(
+    grid: &Grid,
+    line: &[Pos],
+    start: S,
+    step: impl Fn(S, Number) -> Option<S>,
+    accept: impl Fn(S) -> bool,
+    eliminations: &mut Eliminations,
+) {
+    let mut layers = vec![HashSet::from([start])];
+    for &pos in line {
+        let mut next = HashSet::new();
+        for &state in layers.last().unwrap() {
+            next.extend(grid[pos].possibilities().filter_map(|n| step(state, n)));
+        }
+        layers.push(next);
+    }
+
+    let mut alive: HashSet<S> = layers
+        .pop()
+        .unwrap()
+        .into_iter()
+        .filter(|&s| accept(s))
+        .collect();
+    for (layer, &pos) in layers.into_iter().zip(line).rev() {
+        let mut allowed = Field::empty();
+        let mut previous = HashSet::new();
+        for state in layer {
+            for n in grid[pos].possibilities() {
+                if step(state, n).is_some_and(|next| alive.contains(&next)) {
+                    allowed.insert(n);
+                    previous.insert(state);
+                }
+            }
+        }
+        if allowed.is_empty() {
+            eliminations.contradiction();
+            return;
+        }
+        eliminations.restrict(pos, allowed);
+        alive = previous;
+    }
+}
+
+/// Like [prune], but for a row or column, where all numbers are distinct
+fn prune_line<S: Copy + Eq + Hash>(
+    grid: &Grid,
+    line: &[Pos; 9],
+    start: S,
+    step: impl Fn(S, Number, Field) -> Option<S>,
+    accept: impl Fn(S) -> bool,
+    eliminations: &mut Eliminations,
+) {
+    let step = |(used, state): (Field, S), n: Number| {
+        if used.contains(n) {
+            return None;
+        }
+        Some((used | Field::from(n), step(state, n, used)?))
+    };
+    let accept = |(_, state)| accept(state);
+    prune(
+        grid,
+        line,
+        (Field::empty(), start),
+        step,
+        accept,
+        eliminations,
+    );
+}
+
+fn solutions(grid: &Grid, line: impl IntoIterator<Item = Pos>) -> Option<Vec<u8>> {
+    line.into_iter()
+        .map(|pos| grid[pos].solution().map(Number::as_u8))
+        .collect()
+}
+
+impl Sandwich {
+    /// Creates a sandwich clue for the row or column `index`. Panics if the
+    /// index is greater than 8, or the sum is greater than 35.
+    pub fn new(side: Side, index: u8, sum: u8) -> Self {
+        if index > 8 || sum > 35 {
+            panic!("index={index} or sum={sum} is too large");
+        }
+        Sandwich { side, index, sum }
+    }
+
+    /// Returns the side of the clue
+    pub fn side(self) -> Side {
+        self.side
+    }
+
+    /// Returns the index of the row or column
+    pub fn index(self) -> u8 {
+        self.index
+    }
+
+    /// Returns the sum of the numbers between the 1 and the 9
+    pub fn sum(self) -> u8 {
+        self.sum
+    }
+}
+
+fn is_crust(n: Number) -> bool {
+    n == Number::N1 || n == Number::N9
+}
+
+impl Constraint for Sandwich {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        let line = self.side.line(self.index);
+        // the state is the sum so far, and the number of crusts are counted
+        // in the numbers that were used
+        let step = |sum: u8, n: Number, used: Field| {
+            let crusts = used.possibilities().filter(|&n| is_crust(n)).count();
+            if is_crust(n) || crusts != 1 {
+                return Some(sum);
+            }
+            let sum = sum + n.as_u8();
+            (sum <= self.sum).then_some(sum)
+        };
+        prune_line(grid, &line, 0, step, |sum| sum == self.sum, eliminations);
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        solutions(grid, self.side.line(self.index)).is_some_and(|numbers| {
+            let mut crusts = numbers
+                .iter()
+                .enumerate()
+                .filter(|&(_, &n)| n == 1 || n == 9);
+            match (crusts.next(), crusts.next()) {
+                (Some((a, _)), Some((b, _))) => numbers[a + 1..b].iter().sum::<u8>() == self.sum,
+                _ => false,
+            }
+        })
+    }
+}
+
+impl Skyscraper {
+    /// Creates a skyscraper clue for the row or column `index`. Panics if
+    /// the index is greater than 8, or the count isn't between 1 and 9.
+    pub fn new(side: Side, index: u8, count: u8) -> Self {
+        if index > 8 || !(1..=9).contains(&count) {
+            panic!("index={index} or count={count} is invalid");
+        }
+        Skyscraper { side, index, count }
+    }
+
+    /// Returns the side of the clue
+    pub fn side(self) -> Side {
+        self.side
+    }
+
+    /// Returns the index of the row or column
+    pub fn index(self) -> u8 {
+        self.index
+    }
+
+    /// Returns the number of visible skyscrapers
+    pub fn count(self) -> u8 {
+        self.count
+    }
+}
+
+impl Constraint for Skyscraper {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        let line = self.side.line(self.index);
+        // the state is the highest skyscraper so far, and the number of
+        // visible skyscrapers
+        let step = |(highest, count): (u8, u8), n: Number, _| {
+            let n = n.as_u8();
+            if n < highest {
+                return Some((highest, count));
+            }
+            let count = count + 1;
+            (count <= self.count && (n < 9 || count == self.count)).then_some((n, count))
+        };
+        let accept = |(_, count)| count == self.count;
+        prune_line(grid, &line, (0, 0), step, accept, eliminations);
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        solutions(grid, self.side.line(self.index)).is_some_and(|numbers| {
+            let mut highest = 0;
+            let visible = numbers.iter().filter(|&&n| {
+                let is_visible = n > highest;
+                highest = highest.max(n);
+                is_visible
+            });
+            visible.count() == self.count as usize
+        })
+    }
+}
+
+impl XSum {
+    /// Creates an X-sum clue for the row or column `index`. Panics if the
+    /// index is greater than 8, or the sum is greater than 45.
+    pub fn new(side: Side, index: u8, sum: u8) -> Self {
+        if index > 8 || sum > 45 {
+            panic!("index={index} or sum={sum} is too large");
+        }
+        XSum { side, index, sum }
+    }
+
+    /// Returns the side of the clue
+    pub fn side(self) -> Side {
+        self.side
+    }
+
+    /// Returns the index of the row or column
+    pub fn index(self) -> u8 {
+        self.index
+    }
+
+    /// Returns the sum of the first X numbers
+    pub fn sum(self) -> u8 {
+        self.sum
+    }
+}
+
+impl Constraint for XSum {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        let line = self.side.line(self.index);
+        // the state is the first number and the sum so far, and the number
+        // of numbers so far is the number of used numbers
+        let step = |(x, sum): (u8, u8), n: Number, used: Field| {
+            let x = if x == 0 { n.as_u8() } else { x };
+            let len = used.len() as u8 + 1;
+            if len > x {
+                return Some((x, sum));
+            }
+            let sum = sum + n.as_u8();
+            (sum <= self.sum && (len < x || sum == self.sum)).then_some((x, sum))
+        };
+        let accept = |(_, sum)| sum == self.sum;
+        prune_line(grid, &line, (0, 0), step, accept, eliminations);
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        solutions(grid, self.side.line(self.index))
+            .is_some_and(|numbers| numbers[..numbers[0] as usize].iter().sum::<u8>() == self.sum)
+    }
+}
+
+impl LittleKiller {
+    /// Creates a little killer clue. Panics if the sum is greater than 81.
+    pub fn new(diagonal: Diagonal, sum: u8) -> Self {
+        if sum > 81 {
+            panic!("sum={sum} is too large");
+        }
+        LittleKiller { diagonal, sum }
+    }
+
+    /// Returns the diagonal
+    pub fn diagonal(self) -> Diagonal {
+        self.diagonal
+    }
+
+    /// Returns the sum of the numbers along the diagonal
+    pub fn sum(self) -> u8 {
+        self.sum
+    }
+}
+
+impl Constraint for LittleKiller {
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        let line: Vec<Pos> = self.diagonal.positions().collect();
+        let step = |sum: u8, n: Number| {
+            let sum = sum + n.as_u8();
+            (sum <= self.sum).then_some(sum)
+        };
+        prune(grid, &line, 0, step, |sum| sum == self.sum, eliminations);
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        solutions(grid, self.diagonal.positions())
+            .is_some_and(|numbers| numbers.iter().sum::<u8>() == self.sum)
+    }
+}
+
+#[test]
+fn test_outside_clues() {
+    use crate::Variant;
+
+    let diagonal = Diagonal::new(Pos::new(0, 2), 1, -1);
+    assert_eq!(diagonal.positions().count(), 3);
+    let grid = Variant::new()
+        .with(LittleKiller::new(diagonal, 24))
+        .with(Skyscraper::new(Side::Bottom, 4, 1))
+        .with(XSum::new(Side::Top, 8, 1))
+        .new_grid([[Field::full(); 9]; 9]);
+    assert_eq!(grid[Pos::new(1, 1)], Field::from_bits(0b111_100_000));
+    assert_eq!(grid[Pos::new(4, 8)], Field::new(9));
+    assert_eq!(grid[Pos::new(8, 0)], Field::new(1));
+
+    // clues taken from a solution never rule out the solution
+    let solution = crate::generate_from_seed(0);
+    let mut variant = Variant::new();
+    for index in 0..9 {
+        for side in [Side::Top, Side::Right, Side::Bottom, Side::Left] {
+            let count = (1..=9).map(|count| Skyscraper::new(side, index, count));
+            let x_sum = (0..=45).map(|sum| XSum::new(side, index, sum));
+            variant = variant
+                .with(count.into_iter().find(|c| c.is_valid(&solution)).unwrap())
+                .with(x_sum.into_iter().find(|c| c.is_valid(&solution)).unwrap());
+        }
+        let sandwich = (0..=35).map(|sum| Sandwich::new(Side::Left, index, sum));
+        variant = variant.with(
+            sandwich
+                .into_iter()
+                .find(|c| c.is_valid(&solution))
+                .unwrap(),
+        );
+    }
+    assert!(variant.is_valid(&solution));
+
+    let grid = variant.new_grid([[Field::full(); 9]; 9]);
+    assert!(Pos::iterator().all(|pos| !(grid[pos] & solution[pos]).is_empty()));
+    let mut acc = Vec::new();
+    crate::solve_with_variant(&grid, &variant, &mut acc, crate::SolveOptions::default());
+    assert!(variant.is_valid(&acc[0]));
+}
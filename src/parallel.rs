@@ -0,0 +1,105 @@
+//! Parallel generation and solving, enabled with the `rayon` feature.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rayon::prelude::*;
+
+use crate::solve::for_each_solution;
+use crate::{CancelToken, Grid, SolutionCount, SolveOptions, generate_from_seed};
+
+/// Generates `count` random grids in parallel.
+///
+/// Every grid is generated with [generate_from_seed], using a seed derived
+/// from `seed` and the grid's index. Therefore, the result only depends on
+/// `seed`, not on the number of threads or how the work is distributed.
+///
+/// ### Example
+///
+/// ```
+/// let grids = sudoku::par_generate(42, 100);
+/// assert_eq!(grids.len(), 100);
+/// assert_eq!(grids, sudoku::par_generate(42, 100));
+/// ```
+pub fn par_generate(seed: u64, count: usize) -> Vec<Grid> {
+    (0..count as u64)
+        .into_par_iter()
+        .map(|i| generate_from_seed(derive_seed(seed, i)))
+        .collect()
+}
+
+/// Returns a well-distributed seed for the `i`-th grid, using SplitMix64
+fn derive_seed(seed: u64, i: u64) -> u64 {
+    let mut z = seed.wrapping_add(i.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Counts the solutions of the sudoku grid in parallel, see
+/// [count_solutions](crate::count_solutions).
+///
+/// The top levels of the search tree are expanded until there are enough
+/// subtrees to keep all threads busy, then the subtrees are searched in
+/// parallel. All threads stop as soon as `limit` solutions were found.
+pub fn par_count_solutions(grid: &Grid, limit: u64) -> SolutionCount {
+    let count = AtomicU64::new(0);
+    let done = CancelToken::new();
+    if limit == 0 {
+        done.cancel();
+    }
+    let on_solution = || {
+        if count.fetch_add(1, Ordering::Relaxed) + 1 >= limit {
+            done.cancel();
+        }
+        !done.is_cancelled()
+    };
+
+    let target = rayon::current_num_threads() * 8;
+    let mut frontier = VecDeque::from([grid.clone()]);
+    while frontier.len() < target && !done.is_cancelled() {
+        let Some(grid) = frontier.pop_front() else {
+            break;
+        };
+        let Some(pos) = grid.best_field_to_guess() else {
+            on_solution();
+            continue;
+        };
+        for num in grid[pos].possibilities() {
+            let mut copy = grid.clone();
+            if !copy.set(pos, num) {
+                frontier.push_back(copy);
+            }
+        }
+    }
+
+    let options = SolveOptions::default().with_cancel(done.clone());
+    frontier.into_par_iter().for_each(|grid| {
+        for_each_solution(&grid, None, &options, |_| on_solution());
+    });
+
+    let count = count.into_inner().min(limit);
+    if count < limit {
+        SolutionCount::Exact(count)
+    } else {
+        SolutionCount::AtLeast(limit)
+    }
+}
+
+#[test]
+fn test_par_count_solutions() {
+    let mut grid = Grid::full();
+    let first_row = [3, 1, 2, 6, 4, 5, 9, 7, 8];
+    for (x, n) in first_row.into_iter().enumerate() {
+        grid.set(crate::Pos::new(x as u8, 0), crate::Number::new(n));
+    }
+    for limit in [0, 1, 7, 1000] {
+        assert_eq!(
+            par_count_solutions(&grid, limit),
+            crate::count_solutions(&grid, limit)
+        );
+    }
+
+    let puzzle = par_generate(1, 1).pop().unwrap();
+    assert_eq!(par_count_solutions(&puzzle, 2), SolutionCount::Exact(1));
+}
@@ -0,0 +1,183 @@
+use crate::error::{ParseError, ParseErrorKind};
+use crate::{Field, Grid, Number};
+
+impl Grid {
+    /// Formats the grid as a pencil-mark grid, where every field lists all
+    /// its possibilities, and blocks are separated by lines:
+    ///
+    /// ```text
+    /// .---------------.----------------.-------------.
+    /// | 1    7   29   | 456  8    3    | 29   45  6  |
+    /// ...
+    /// :---------------+----------------+-------------:
+    /// ...
+    /// '---------------'----------------'-------------'
+    /// ```
+    ///
+    /// Fields without any possibilities are written as `0`. The result can be
+    /// parsed with [Grid::from_pencil_marks].
+    pub fn to_pencil_marks(&self) -> String {
+        layout(&self.fields.map(|row| row.map(pencil_marks)))
+    }
+
+    /// Parses a pencil-mark grid as produced by [Grid::to_pencil_marks].
+    ///
+    /// Every line containing digits must contain the possibilities of 9
+    /// fields, separated by whitespace or any of the characters `|:+-.'*`.
+    /// All other lines must only contain separators. The fields are taken
+    /// as-is, without removing any possibilities that contradict solved
+    /// fields.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use sudoku::{Grid, Pos};
+    ///
+    /// let grid = sudoku::generate(&mut rand::rng());
+    /// let mut pencil_marks = grid.clone();
+    /// pencil_marks[Pos::new(0, 0)] = sudoku::Field::full();
+    ///
+    /// let text = pencil_marks.to_pencil_marks();
+    /// assert_eq!(Grid::from_pencil_marks(&text), Ok(pencil_marks));
+    /// ```
+    pub fn from_pencil_marks(s: &str) -> Result<Grid, ParseError> {
+        let mut grid = Grid::full();
+        let mut row = 0;
+
+        for (li, line) in s.lines().enumerate() {
+            let err = |kind| ParseError::new(li + 1, kind);
+
+            let tokens = line
+                .split(|c: char| c.is_whitespace() || "|:+-.'*".contains(c))
+                .filter(|token| !token.is_empty());
+
+            let mut col = 0;
+            for token in tokens {
+                let field = parse_field(token).map_err(|c| err(ParseErrorKind::InvalidChar(c)))?;
+                if col < 9 && row < 9 {
+                    grid.fields[row][col] = field;
+                }
+                col += 1;
+            }
+
+            if col != 0 {
+                if col != 9 {
+                    return Err(err(ParseErrorKind::InvalidLength {
+                        expected: 9,
+                        found: col,
+                    }));
+                }
+                row += 1;
+            }
+        }
+
+        if row != 9 {
+            return Err(ParseError::new(
+                s.lines().count(),
+                ParseErrorKind::InvalidLength {
+                    expected: 9,
+                    found: row,
+                },
+            ));
+        }
+        Ok(grid)
+    }
+}
+
+/// Lays out the cells like [Grid::to_pencil_marks], with aligned columns and
+/// lines between the blocks
+pub(crate) fn layout(cells: &[[String; 9]; 9]) -> String {
+    let mut widths = [1; 9];
+    for row in cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let separator = |left: char, middle: char, right: char| {
+        let mut line = String::new();
+        for stack in 0..3 {
+            line.push(if stack == 0 { left } else { middle });
+            let width: usize = widths[stack * 3..stack * 3 + 3].iter().sum();
+            line.extend(std::iter::repeat_n('-', width + 6));
+        }
+        line.push(right);
+        line.push('\n');
+        line
+    };
+
+    let mut result = separator('.', '.', '.');
+    for (ri, row) in cells.iter().enumerate() {
+        for (ci, cell) in row.iter().enumerate() {
+            result.push_str(match ci {
+                0 => "| ",
+                3 | 6 => " | ",
+                _ => "  ",
+            });
+            result.push_str(cell);
+            result.extend(std::iter::repeat_n(' ', widths[ci] - cell.len()));
+        }
+        result.push_str(" |\n");
+        if ri == 2 || ri == 5 {
+            result.push_str(&separator(':', '+', ':'));
+        }
+    }
+    result.push_str(&separator('\'', '\'', '\''));
+    result
+}
+
+/// Returns the possibilities of a field, or `0` if it has none
+pub(crate) fn pencil_marks(field: Field) -> String {
+    if field.is_empty() {
+        "0".to_string()
+    } else {
+        field.possibilities().map(Number::as_str).collect()
+    }
+}
+
+/// Parses the possibilities of a field. On failure, the invalid character
+/// is returned.
+fn parse_field(token: &str) -> Result<Field, char> {
+    if token == "0" {
+        return Ok(Field::empty());
+    }
+    token
+        .chars()
+        .map(|c| match c {
+            '1'..='9' => Ok(Number::new(c as u8 - b'0')),
+            _ => Err(c),
+        })
+        .collect()
+}
+
+#[test]
+fn test_pencil_marks() {
+    let mut grid = Grid::new([
+        crate::row![_ 7 _  _ _ 4  _ _ _],
+        crate::row![_ _ _  _ 5 _  1 _ _],
+        crate::row![8 _ _  _ _ _  6 _ _],
+        crate::row![_ _ _  6 _ _  2 _ _],
+        crate::row![_ _ 1  _ _ _  _ _ _],
+        crate::row![_ 5 _  _ _ _  _ _ _],
+        crate::row![2 _ _  1 _ _  _ 4 _],
+        crate::row![_ _ _  3 _ _  _ 5 _],
+        crate::row![6 _ _  _ 7 _  _ _ _],
+    ]);
+    grid[crate::Pos::new(8, 8)] = Field::empty();
+
+    let text = grid.to_pencil_marks();
+    assert_eq!(text.lines().count(), 13);
+    assert_eq!(Grid::from_pencil_marks(&text), Ok(grid));
+
+    let err = Grid::from_pencil_marks("1 2 3\n").unwrap_err();
+    assert_eq!(err.line(), 1);
+    assert_eq!(
+        err.kind(),
+        &ParseErrorKind::InvalidLength {
+            expected: 9,
+            found: 3
+        }
+    );
+    let err = Grid::from_pencil_marks(&text.replacen('7', "x", 1)).unwrap_err();
+    assert_eq!(err.kind(), &ParseErrorKind::InvalidChar('x'));
+}
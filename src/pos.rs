@@ -4,7 +4,7 @@ use std::fmt;
 /// [Grid](super::Grid).
 ///
 /// To iterate over all positions, use [Pos::iterator()].
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(align(2))]
 pub struct Pos {
     x: u8,
@@ -0,0 +1,278 @@
+use rand::seq::IndexedRandom;
+
+use crate::error::RegionError;
+use crate::{Grid, Pos, SolveOptions, Variant};
+
+/// A region map for jigsaw sudoku, where nine irregular regions replace the
+/// 3x3 blocks. Every region consists of nine orthogonally connected fields,
+/// and must contain every number exactly once.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Regions, SolutionCount, Variant};
+/// use sudoku::{count_solutions_with_variant, generate_with_variant};
+///
+/// let mut rng = rand::rng();
+/// let regions = Regions::random(&mut rng);
+///
+/// let variant = Variant::jigsaw(regions);
+/// let grid = generate_with_variant(&variant, &mut rng);
+/// let count = count_solutions_with_variant(&grid, &variant, 2);
+/// assert_eq!(count, SolutionCount::Exact(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    map: [[u8; 9]; 9],
+    /// The rows, columns and regions
+    houses: Vec<[Pos; 9]>,
+    /// The positions visible from each position, indexed by `y * 9 + x`
+    visible: Vec<Vec<Pos>>,
+}
+
+impl Regions {
+    /// Creates a region map from the region number (0 to 8) of every field,
+    /// indexed by `[y][x]`. Returns an error if the regions don't consist of
+    /// nine connected fields each.
+    pub fn new(map: [[u8; 9]; 9]) -> Result<Self, RegionError> {
+        validate(&map)?;
+
+        let visible = (0..81)
+            .map(|i| {
+                let pos = Pos::new(i % 9, i / 9);
+                let region = map[pos.y() as usize][pos.x() as usize];
+                Pos::iterator()
+                    .filter(|&other| {
+                        other != pos
+                            && (other.x() == pos.x()
+                                || other.y() == pos.y()
+                                || map[other.y() as usize][other.x() as usize] == region)
+                    })
+                    .collect()
+            })
+            .collect();
+        let houses = (0..9)
+            .flat_map(|i| {
+                let region: Vec<Pos> = Pos::iterator()
+                    .filter(|p| map[p.y() as usize][p.x() as usize] == i)
+                    .collect();
+                [
+                    std::array::from_fn(|j| Pos::new(j as u8, i)),
+                    std::array::from_fn(|j| Pos::new(i, j as u8)),
+                    region.try_into().unwrap(),
+                ]
+            })
+            .collect();
+        Ok(Regions {
+            map,
+            houses,
+            visible,
+        })
+    }
+
+    /// Returns the classic 3x3 blocks
+    pub fn blocks() -> Self {
+        let map = std::array::from_fn(|y| std::array::from_fn(|x| (y / 3 * 3 + x / 3) as u8));
+        Regions::new(map).unwrap()
+    }
+
+    /// Generates a random region map, where a sudoku grid can be filled in.
+    ///
+    /// Starting with the 3x3 blocks, fields are repeatedly swapped between
+    /// neighboring regions, as long as the regions stay connected.
+    pub fn random(rng: &mut impl rand::Rng) -> Self {
+        loop {
+            let mut map = Regions::blocks().map;
+            for _ in 0..500 {
+                swap_random(&mut map, rng);
+            }
+
+            let regions = Regions::new(map).unwrap();
+            if regions.has_solution() {
+                return regions;
+            }
+        }
+    }
+
+    /// Returns the region number (0 to 8) of the field
+    pub fn region(&self, pos: Pos) -> u8 {
+        self.map[pos.y() as usize][pos.x() as usize]
+    }
+
+    /// Returns the fields of a region
+    pub fn positions(&self, region: u8) -> impl Iterator<Item = Pos> + '_ {
+        Pos::iterator().filter(move |&pos| self.region(pos) == region)
+    }
+
+    /// Returns all the positions in the same row, column or region as `pos`,
+    /// excluding `pos`. This replaces [Pos::get_visible_positions].
+    pub fn visible_positions(&self, pos: Pos) -> &[Pos] {
+        &self.visible[pos.y() as usize * 9 + pos.x() as usize]
+    }
+
+    /// Returns the rows, columns and regions
+    pub(crate) fn houses(&self) -> &[[Pos; 9]] {
+        &self.houses
+    }
+
+    /// Returns whether a sudoku grid can be filled in. Some layouts are hard
+    /// to prove unsolvable, so they are rejected after a number of guesses.
+    fn has_solution(&self) -> bool {
+        let variant = Variant::jigsaw(self.clone());
+        let options = SolveOptions::default().with_max_guesses(10_000);
+        let mut found = false;
+        crate::solve::for_each_solution(&Grid::full(), Some(&variant), &options, |_| {
+            found = true;
+            false
+        });
+        found
+    }
+}
+
+impl Variant {
+    /// Jigsaw sudoku: The 3x3 blocks are replaced with irregular regions
+    pub fn jigsaw(regions: Regions) -> Self {
+        Variant::new().with_regions(regions)
+    }
+}
+
+fn validate(map: &[[u8; 9]; 9]) -> Result<(), RegionError> {
+    let mut sizes = [0; 9];
+    for pos in Pos::iterator() {
+        let region = map[pos.y() as usize][pos.x() as usize];
+        if region > 8 {
+            return Err(RegionError::OutOfRange(pos));
+        }
+        sizes[region as usize] += 1;
+    }
+    for (region, &size) in sizes.iter().enumerate() {
+        if size != 9 {
+            return Err(RegionError::WrongSize {
+                region: region as u8,
+                size,
+            });
+        }
+    }
+    for region in 0..9 {
+        if !is_connected(map, region) {
+            return Err(RegionError::Disconnected(region));
+        }
+    }
+    Ok(())
+}
+
+/// Returns whether the fields of the region are orthogonally connected, using
+/// a flood fill
+fn is_connected(map: &[[u8; 9]; 9], region: u8) -> bool {
+    let Some(start) = Pos::iterator().find(|p| map[p.y() as usize][p.x() as usize] == region)
+    else {
+        return true;
+    };
+    let mut visited = [[false; 9]; 9];
+    visited[start.y() as usize][start.x() as usize] = true;
+    let mut stack = vec![start];
+    let mut count = 1;
+    while let Some(pos) = stack.pop() {
+        for next in neighbors(pos) {
+            let (x, y) = (next.x() as usize, next.y() as usize);
+            if map[y][x] == region && !visited[y][x] {
+                visited[y][x] = true;
+                count += 1;
+                stack.push(next);
+            }
+        }
+    }
+    count
+        == Pos::iterator()
+            .filter(|p| map[p.y() as usize][p.x() as usize] == region)
+            .count()
+}
+
+/// Returns the orthogonally adjacent positions
+fn neighbors(pos: Pos) -> impl Iterator<Item = Pos> {
+    let (x, y) = (pos.x() as i8, pos.y() as i8);
+    [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+        .into_iter()
+        .filter(|&(x, y)| (0..9).contains(&x) && (0..9).contains(&y))
+        .map(|(x, y)| Pos::new(x as u8, y as u8))
+}
+
+/// Moves a random field from one region to a neighboring region, and a field
+/// back in exchange, unless this disconnects one of the regions
+fn swap_random(map: &mut [[u8; 9]; 9], rng: &mut impl rand::Rng) {
+    let region = |map: &[[u8; 9]; 9], pos: Pos| map[pos.y() as usize][pos.x() as usize];
+
+    let a = Pos::new(rng.random_range(0..9), rng.random_range(0..9));
+    let ra = region(map, a);
+    let others: Vec<Pos> = neighbors(a).filter(|&p| region(map, p) != ra).collect();
+    let Some(&b) = others.choose(rng) else {
+        return;
+    };
+    let rb = region(map, b);
+
+    let candidates: Vec<Pos> = Pos::iterator()
+        .filter(|&p| p != a && region(map, p) == rb)
+        .filter(|&p| neighbors(p).any(|n| n != a && region(map, n) == ra))
+        .collect();
+    let Some(&c) = candidates.choose(rng) else {
+        return;
+    };
+
+    map[a.y() as usize][a.x() as usize] = rb;
+    map[c.y() as usize][c.x() as usize] = ra;
+    if !is_connected(map, ra) || !is_connected(map, rb) {
+        map[a.y() as usize][a.x() as usize] = ra;
+        map[c.y() as usize][c.x() as usize] = rb;
+    }
+}
+
+#[test]
+fn test_regions() {
+    let mut map = Regions::blocks().map;
+    assert_eq!(Regions::new(map), Ok(Regions::blocks()));
+
+    map[0][0] = 9;
+    assert_eq!(
+        Regions::new(map),
+        Err(RegionError::OutOfRange(Pos::new(0, 0)))
+    );
+    map[0][0] = 1;
+    assert_eq!(
+        Regions::new(map),
+        Err(RegionError::WrongSize { region: 0, size: 8 })
+    );
+    map[0][0] = 0;
+    map[1][1] = 4;
+    map[4][4] = 0;
+    assert_eq!(Regions::new(map), Err(RegionError::Disconnected(0)));
+
+    let mut rng = <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(0);
+    let regions = Regions::random(&mut rng);
+    for region in 0..9 {
+        assert_eq!(regions.positions(region).count(), 9);
+    }
+
+    let variant = Variant::jigsaw(regions.clone());
+    let solution = crate::variant::check_generated(&variant, 1);
+    for region in 0..9 {
+        let numbers: crate::Field = regions
+            .positions(region)
+            .filter_map(|pos| solution[pos].solution())
+            .collect();
+        assert_eq!(numbers, crate::Field::full());
+    }
+    assert!(!variant.is_valid(&crate::generate_from_seed(0)));
+
+    // the pencil marks of the grid are kept when solving
+    let puzzle = variant.remove_givens(&solution, &mut rng);
+    let mut acc = Vec::new();
+    crate::solve_with_variant(&puzzle, &variant, &mut acc, SolveOptions::default());
+    let mut marked = puzzle.clone();
+    let pos = Pos::iterator()
+        .find(|&pos| puzzle[pos].solution().is_none())
+        .unwrap();
+    marked[pos] -= solution[pos];
+    let count = crate::count_solutions_with_variant(&marked, &variant, 1);
+    assert_eq!(count, crate::SolutionCount::Exact(0));
+    assert_eq!(acc, [solution]);
+}
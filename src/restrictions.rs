@@ -0,0 +1,217 @@
+use std::fmt;
+
+use crate::error::ParseErrorKind;
+use crate::pencil_marks::layout;
+use crate::{Constraint, Eliminations, Field, Grid, Number, Pos};
+
+/// Restricts individual fields to a subset of the numbers, e.g. to even or
+/// odd numbers. Unlike givens, restrictions are rules of the puzzle, so they
+/// are kept when givens are removed.
+///
+/// When formatted with [Display](fmt::Display), restrictions are laid out
+/// like [Grid::to_pencil_marks]. Even and odd fields are written as `E` and
+/// `O`, other restricted fields as their allowed numbers in brackets, e.g.
+/// `[1234]`, and unrestricted fields as `.`. Use
+/// [Puzzle](crate::formats::Puzzle) to show them together with a grid.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Field, Pos, Restrictions, Variant};
+///
+/// let restrictions = Restrictions::new()
+///     .with_even(Pos::new(0, 0))
+///     .with(Pos::new(1, 0), Field::from_bits(0b1111));
+/// let grid = Variant::new().with(restrictions).new_grid([[Field::full(); 9]; 9]);
+///
+/// assert_eq!(grid[Pos::new(0, 0)], Field::from_bits(0b010_101_010));
+/// assert_eq!(grid[Pos::new(1, 0)], Field::from_bits(0b000_001_111));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Restrictions {
+    pub(crate) fields: [[Field; 9]; 9],
+}
+
+impl Restrictions {
+    /// The even numbers
+    pub const EVEN: Field = Field::from_bits(0b010_101_010);
+
+    /// The odd numbers
+    pub const ODD: Field = Field::from_bits(0b101_010_101);
+
+    /// Creates restrictions where every field can contain any number
+    pub fn new() -> Self {
+        Restrictions {
+            fields: [[Field::full(); 9]; 9],
+        }
+    }
+
+    /// Restricts a field to the allowed numbers, replacing any previous
+    /// restriction of the field. Panics if no number is allowed.
+    pub fn with(mut self, pos: Pos, allowed: Field) -> Self {
+        if allowed.is_empty() {
+            panic!("{pos:?} must allow at least one number");
+        }
+        self.fields[pos.y() as usize][pos.x() as usize] = allowed;
+        self
+    }
+
+    /// Restricts a field to even numbers
+    pub fn with_even(self, pos: Pos) -> Self {
+        self.with(pos, Restrictions::EVEN)
+    }
+
+    /// Restricts a field to odd numbers
+    pub fn with_odd(self, pos: Pos) -> Self {
+        self.with(pos, Restrictions::ODD)
+    }
+
+    /// Returns the numbers allowed in a field
+    pub fn allowed(&self, pos: Pos) -> Field {
+        self.fields[pos.y() as usize][pos.x() as usize]
+    }
+
+    /// Returns the restricted fields and their allowed numbers
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, Field)> + '_ {
+        Pos::iterator()
+            .map(|pos| (pos, self.allowed(pos)))
+            .filter(|&(_, allowed)| allowed != Field::full())
+    }
+
+    /// Returns whether no field is restricted
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+impl Default for Restrictions {
+    /// Returns restrictions where every field can contain any number, see
+    /// [Restrictions::new]
+    fn default() -> Self {
+        Restrictions::new()
+    }
+}
+
+impl Constraint for Restrictions {
+    fn eliminations(&self, _: &Grid, eliminations: &mut Eliminations) {
+        for (pos, allowed) in self.iter() {
+            eliminations.restrict(pos, allowed);
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        Pos::iterator().all(|pos| {
+            grid[pos]
+                .solution()
+                .is_none_or(|n| self.allowed(pos).contains(n))
+        })
+    }
+}
+
+impl fmt::Display for Restrictions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&layout(&self.fields.map(|row| row.map(format_allowed))))
+    }
+}
+
+/// Returns `E` or `O` for even and odd fields, `.` for unrestricted fields,
+/// and otherwise the allowed numbers in brackets, e.g. `[1234]`
+pub(crate) fn format_allowed(allowed: Field) -> String {
+    match allowed {
+        Restrictions::EVEN => "E".to_string(),
+        Restrictions::ODD => "O".to_string(),
+        _ if allowed == Field::full() => ".".to_string(),
+        _ => format!(
+            "[{}]",
+            allowed
+                .possibilities()
+                .map(Number::as_str)
+                .collect::<String>()
+        ),
+    }
+}
+
+/// Returns the allowed numbers of all fields in a line of 81 fields, see
+/// [format_allowed]
+pub(crate) fn format_line(restrictions: &Restrictions) -> String {
+    restrictions
+        .fields
+        .iter()
+        .flatten()
+        .map(|&allowed| format_allowed(allowed))
+        .collect()
+}
+
+/// Parses a sequence of fields written with [format_allowed], e.g. `.E[123]O`
+pub(crate) fn parse_allowed(s: &str) -> Result<Vec<Field>, ParseErrorKind> {
+    let mut fields = Vec::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        fields.push(match c {
+            '.' => Field::full(),
+            'E' => Restrictions::EVEN,
+            'O' => Restrictions::ODD,
+            '[' => {
+                let mut allowed = Field::empty();
+                loop {
+                    match chars.next() {
+                        Some(c @ '1'..='9') => allowed |= Field::new(c as u8 - b'0'),
+                        Some(']') if !allowed.is_empty() => break allowed,
+                        Some(c) => return Err(ParseErrorKind::InvalidChar(c)),
+                        None => return Err(ParseErrorKind::Missing("]")),
+                    }
+                }
+            }
+            _ => return Err(ParseErrorKind::InvalidChar(c)),
+        });
+    }
+    Ok(fields)
+}
+
+/// Parses a line of 81 fields written with [format_line]
+pub(crate) fn parse_line(s: &str) -> Result<Restrictions, ParseErrorKind> {
+    let fields = parse_allowed(s)?;
+    if fields.len() != 81 {
+        return Err(ParseErrorKind::InvalidLength {
+            expected: 81,
+            found: fields.len(),
+        });
+    }
+    let mut restrictions = Restrictions::new();
+    for (i, allowed) in fields.into_iter().enumerate() {
+        restrictions.fields[i / 9][i % 9] = allowed;
+    }
+    Ok(restrictions)
+}
+
+#[test]
+fn test_restrictions() {
+    use crate::{SolutionCount, Variant};
+
+    let mut rng = <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(0);
+    let solution = crate::generate_from_seed(0);
+    let restrictions =
+        Pos::iterator().fold(Restrictions::new(), |restrictions, pos| {
+            match solution[pos].solution().unwrap().as_u8() % 2 {
+                0 => restrictions.with_even(pos),
+                _ => restrictions.with_odd(pos),
+            }
+        });
+    assert_eq!(restrictions.iter().count(), 81);
+    assert!(restrictions.to_string().starts_with(".---"));
+
+    let pos = Pos::new(4, 4);
+    let wrong = restrictions
+        .clone()
+        .with(pos, Field::full() - solution[pos]);
+    assert!(!wrong.is_valid(&solution));
+    assert!(wrong.is_valid(&Grid::full()));
+
+    let variant = Variant::new().with(restrictions);
+    assert!(variant.is_valid(&solution));
+    let puzzle = variant.remove_givens(&solution, &mut rng);
+    assert_eq!(
+        crate::count_solutions_with_variant(&puzzle, &variant, 2),
+        SolutionCount::Exact(1)
+    );
+}
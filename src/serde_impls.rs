@@ -0,0 +1,364 @@
+//! [serde] implementations, enabled with the `serde` feature.
+//!
+//! Human-readable formats (e.g. JSON) use a compact textual representation:
+//!
+//! - [Number]: the number as an integer
+//! - [Pos]: a `[x, y]` tuple
+//! - [Field]: its possibilities as a string of digits, e.g. `"147"`
+//! - [Grid]: an 81-character string of numbers, with `.` for unsolved
+//!   fields, if the possibilities follow from the solved fields; otherwise,
+//!   a list of 81 [Field]s
+//! - [Restrictions]: a list of `[pos, field]` pairs of the restricted fields
+//!
+//! [Puzzle](crate::formats::Puzzle) and its metadata are serialized as
+//! structs, where the restrictions may be omitted.
+//!
+//! Binary formats store [Field]s as their bit mask. [Grid]s are stored as
+//! bytes: in the encoding of [Grid::to_bytes] if the possibilities follow from
+//! the solved fields, otherwise as the 81 bit masks packed into 92 bytes, 9
+//! bits per field.
+
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use crate::{Field, Grid, Number, Pos, Restrictions};
+
+impl Serialize for Number {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.as_u8())
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let n = u8::deserialize(deserializer)?;
+        if n == 0 || n > 9 {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(n.into()),
+                &"a number between 1 and 9",
+            ));
+        }
+        Ok(Number::new(n))
+    }
+}
+
+impl Serialize for Pos {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.x(), self.y()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pos {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (x, y) = <(u8, u8)>::deserialize(deserializer)?;
+        if x > 8 || y > 8 {
+            return Err(de::Error::custom(format_args!(
+                "x={x} or y={y} is too large"
+            )));
+        }
+        Ok(Pos::new(x, y))
+    }
+}
+
+impl Serialize for Field {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let digits: String = self.possibilities().map(Number::as_str).collect();
+            serializer.serialize_str(&digits)
+        } else {
+            serializer.serialize_u16(self.bits())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(FieldVisitor)
+        } else {
+            let bits = u16::deserialize(deserializer)?;
+            if Field::from_bits(bits).bits() != bits {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(bits.into()),
+                    &"a 9-bit mask",
+                ));
+            }
+            Ok(Field::from_bits(bits))
+        }
+    }
+}
+
+struct FieldVisitor;
+
+impl Visitor<'_> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string of digits between 1 and 9")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+        v.chars()
+            .map(|c| match c {
+                '1'..='9' => Ok(Number::new(c as u8 - b'0')),
+                _ => Err(E::invalid_value(de::Unexpected::Char(c), &self)),
+            })
+            .collect()
+    }
+}
+
+/// The length of the binary form of a grid with arbitrary possibilities.
+/// [Grid::to_bytes] never needs as many bytes.
+const PACKED_LEN: usize = 92;
+
+impl Grid {
+    /// Returns whether the possibilities follow from the solved fields, i.e.
+    /// whether the grid can be stored without its unsolved fields
+    fn is_determined(&self) -> bool {
+        let givens = self.fields.map(|row| {
+            row.map(|field| {
+                if field.solution().is_some() {
+                    field
+                } else {
+                    Field::full()
+                }
+            })
+        });
+        Grid::new(givens) == *self
+    }
+}
+
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            if self.is_determined() {
+                return serializer.serialize_bytes(&self.to_bytes());
+            }
+            let mut bytes = [0u8; PACKED_LEN];
+            for (i, field) in self.fields.iter().flatten().enumerate() {
+                // a mask shifted by up to 7 bits still fits in 2 bytes
+                let bits = field.bits() << (i * 9 % 8);
+                bytes[i * 9 / 8] |= bits as u8;
+                bytes[i * 9 / 8 + 1] |= (bits >> 8) as u8;
+            }
+            return serializer.serialize_bytes(&bytes);
+        }
+
+        if self.is_determined() {
+            let s: String = self
+                .fields
+                .iter()
+                .flatten()
+                .map(|field| field.solution().map_or(".", Number::as_str))
+                .collect();
+            return serializer.serialize_str(&s);
+        }
+
+        let mut tuple = serializer.serialize_tuple(81)?;
+        for field in self.fields.iter().flatten() {
+            tuple.serialize_element(field)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Grid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(GridVisitor)
+        } else {
+            deserializer.deserialize_bytes(GridBytesVisitor)
+        }
+    }
+}
+
+struct GridVisitor;
+
+impl<'de> Visitor<'de> for GridVisitor {
+    type Value = Grid;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string of 81 digits or a sequence of 81 fields")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Grid, E> {
+        if v.chars().count() != 81 {
+            return Err(E::invalid_length(v.chars().count(), &self));
+        }
+
+        let mut fields = [[Field::full(); 9]; 9];
+        for (i, c) in v.chars().enumerate() {
+            fields[i / 9][i % 9] = match c {
+                '1'..='9' => Field::new(c as u8 - b'0'),
+                '.' | '0' | '_' => Field::full(),
+                _ => return Err(E::invalid_value(de::Unexpected::Char(c), &self)),
+            };
+        }
+        Ok(Grid::new(fields))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Grid, A::Error> {
+        let mut grid = Grid::full();
+        for i in 0..81 {
+            grid.fields[i / 9][i % 9] = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<Field>()?.is_some() {
+            return Err(de::Error::invalid_length(82, &self));
+        }
+        Ok(grid)
+    }
+}
+
+struct GridBytesVisitor;
+
+impl<'de> Visitor<'de> for GridBytesVisitor {
+    type Value = Grid;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a grid encoded as bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Grid, E> {
+        if v.len() != PACKED_LEN {
+            return Grid::from_bytes(v)
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Bytes(v), &self));
+        }
+
+        let mut grid = Grid::full();
+        for i in 0..81 {
+            let bits = u16::from_le_bytes([v[i * 9 / 8], v[i * 9 / 8 + 1]]) >> (i * 9 % 8);
+            grid.fields[i / 9][i % 9] = Field::from_bits(bits & 0b111_111_111);
+        }
+        // the 7 bits after the last field must be zero
+        if v[PACKED_LEN - 1] >> 1 != 0 {
+            return Err(E::invalid_value(de::Unexpected::Bytes(v), &self));
+        }
+        Ok(grid)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Grid, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl Serialize for Restrictions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // collected first, since binary formats need the length up front
+        serializer.collect_seq(self.iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'de> Deserialize<'de> for Restrictions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let restricted = Vec::<(Pos, Field)>::deserialize(deserializer)?;
+        let mut restrictions = Restrictions::new();
+        for (pos, allowed) in restricted {
+            if allowed.is_empty() {
+                return Err(de::Error::custom(format_args!(
+                    "{pos:?} must allow at least one number"
+                )));
+            }
+            restrictions = restrictions.with(pos, allowed);
+        }
+        Ok(restrictions)
+    }
+}
+
+#[test]
+fn test_json() {
+    let grid = Grid::new([
+        crate::row![_ 7 _  _ _ 4  _ _ _],
+        crate::row![_ _ _  _ 5 _  1 _ _],
+        crate::row![8 _ _  _ _ _  6 _ _],
+        crate::row![_ _ _  6 _ _  2 _ _],
+        crate::row![_ _ 1  _ _ _  _ _ _],
+        crate::row![_ 5 _  _ _ _  _ _ _],
+        crate::row![2 _ _  1 _ _  _ 4 _],
+        crate::row![_ _ _  3 _ _  _ 5 _],
+        crate::row![6 _ _  _ 7 _  _ _ _],
+    ]);
+    let json = serde_json::to_string(&grid).unwrap();
+    assert_eq!(json.len(), 83);
+    assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), grid);
+
+    let mut edited = grid.clone();
+    edited[Pos::new(0, 0)].remove(Number::N1);
+    let json = serde_json::to_string(&edited).unwrap();
+    assert!(json.starts_with("[\""));
+    assert_eq!(serde_json::from_str::<Grid>(&json).unwrap(), edited);
+
+    let field: Field = serde_json::from_str("\"139\"").unwrap();
+    assert_eq!(field.len(), 3);
+    assert_eq!(serde_json::to_string(&field).unwrap(), "\"139\"");
+    assert_eq!(serde_json::to_string(&Pos::new(3, 4)).unwrap(), "[3,4]");
+    assert!(serde_json::from_str::<Number>("0").is_err());
+
+    let restrictions = Restrictions::new()
+        .with_even(Pos::new(2, 0))
+        .with(Pos::new(0, 1), Field::from_bits(0b111));
+    let json = serde_json::to_string(&restrictions).unwrap();
+    assert_eq!(json, r#"[[[0,1],"123"],[[2,0],"2468"]]"#);
+    assert_eq!(
+        serde_json::from_str::<Restrictions>(&json).unwrap(),
+        restrictions
+    );
+    assert!(serde_json::from_str::<Restrictions>(r#"[[[0,0],""]]"#).is_err());
+
+    let puzzle = crate::formats::Puzzle {
+        grid,
+        restrictions,
+        metadata: crate::formats::Metadata {
+            author: Some("A".to_string()),
+            ..Default::default()
+        },
+    };
+    let json = serde_json::to_string(&puzzle).unwrap();
+    assert!(json.contains(r#""restrictions":[[[0,1],"123"],"#));
+    assert_eq!(
+        serde_json::from_str::<crate::formats::Puzzle>(&json).unwrap(),
+        puzzle
+    );
+    let json = json.replace(r#""restrictions":[[[0,1],"123"],[[2,0],"2468"]],"#, "");
+    let plain = serde_json::from_str::<crate::formats::Puzzle>(&json).unwrap();
+    assert!(plain.restrictions.is_empty());
+    assert_eq!(plain.grid, puzzle.grid);
+}
+
+#[test]
+fn test_binary() {
+    let solution = crate::generate_from_seed(1);
+    let bytes = bincode::serialize(&solution).unwrap();
+    // the length prefix takes 8 bytes
+    assert!(bytes.len() <= 8 + 11);
+    assert_eq!(bincode::deserialize::<Grid>(&bytes).unwrap(), solution);
+
+    let mut pencil_marks = solution.clone();
+    pencil_marks.fields[0][0] = Field::from_bits(0b100_000_001);
+    pencil_marks.fields[8][8] = Field::full();
+    let bytes = bincode::serialize(&pencil_marks).unwrap();
+    assert_eq!(bytes.len(), 8 + PACKED_LEN);
+    assert_eq!(bincode::deserialize::<Grid>(&bytes).unwrap(), pencil_marks);
+
+    let mut invalid = bytes.clone();
+    *invalid.last_mut().unwrap() = 0xff;
+    assert!(bincode::deserialize::<Grid>(&invalid).is_err());
+
+    let puzzle = crate::formats::Puzzle {
+        grid: solution,
+        restrictions: Restrictions::new().with_odd(Pos::new(4, 4)),
+        metadata: Default::default(),
+    };
+    let bytes = bincode::serialize(&puzzle).unwrap();
+    assert_eq!(
+        bincode::deserialize::<crate::formats::Puzzle>(&bytes).unwrap(),
+        puzzle
+    );
+}
@@ -0,0 +1,509 @@
+//! Sudoku grids of other sizes than 9x9, e.g. 4x4, 6x6 or 16x16.
+//!
+//! [Grid] is hard-coded to 9x9 to keep it fast. [SizedGrid] supports any
+//! [Geometry] with up to 32 numbers, at the cost of some speed.
+//!
+//! Sized grids can be parsed, printed, solved with the same search and
+//! [SolveOptions] as [Grid], and generated. Everything else, e.g.
+//! [Variant](crate::Variant)s, the [formats](crate::formats), binary
+//! encoding and serde support, is only available for 9x9 grids; use
+//! [SizedGrid::to_grid] to convert.
+
+use std::fmt;
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::solve::{
+    SearchGrid, Stats, count, for_each_solution, random_solution, search_with_stats,
+};
+use crate::{Grid, Number, Pos, SolutionCount, SolveOptions, SolveStats, SolveStatus, Variant};
+
+/// The shape of a sudoku grid: The grid is divided into boxes that are
+/// `box_width` fields wide and `box_height` fields high, and contains the
+/// numbers from 1 to `box_width * box_height`.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::Geometry;
+///
+/// assert_eq!(Geometry::SIZE_6.size(), 6);
+/// assert_eq!(Geometry::SIZE_6.box_width(), 3);
+/// assert_eq!(Geometry::SIZE_6.box_height(), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Geometry {
+    box_width: u8,
+    box_height: u8,
+}
+
+impl Geometry {
+    /// 4x4 grid with 2x2 boxes
+    pub const SIZE_4: Geometry = Geometry::new(2, 2);
+    /// 6x6 grid with boxes that are 3 fields wide and 2 fields high
+    pub const SIZE_6: Geometry = Geometry::new(3, 2);
+    /// The classic 9x9 grid with 3x3 boxes
+    pub const SIZE_9: Geometry = Geometry::new(3, 3);
+    /// 12x12 grid with boxes that are 4 fields wide and 3 fields high
+    pub const SIZE_12: Geometry = Geometry::new(4, 3);
+    /// 16x16 grid with 4x4 boxes
+    pub const SIZE_16: Geometry = Geometry::new(4, 4);
+    /// 25x25 grid with 5x5 boxes
+    pub const SIZE_25: Geometry = Geometry::new(5, 5);
+
+    /// Creates a geometry. Panics if a dimension is 0, or if the grid would
+    /// contain more than 32 numbers.
+    pub const fn new(box_width: u8, box_height: u8) -> Self {
+        if box_width == 0 || box_height == 0 || box_width as u32 * box_height as u32 > 32 {
+            panic!("Invalid geometry");
+        }
+        Geometry {
+            box_width,
+            box_height,
+        }
+    }
+
+    /// Returns the width of a box
+    pub const fn box_width(self) -> usize {
+        self.box_width as usize
+    }
+
+    /// Returns the height of a box
+    pub const fn box_height(self) -> usize {
+        self.box_height as usize
+    }
+
+    /// Returns the number of rows, columns and boxes, which is also the
+    /// largest number in the grid
+    pub const fn size(self) -> usize {
+        self.box_width() * self.box_height()
+    }
+
+    /// Returns the bit mask containing all numbers
+    const fn mask(self) -> u32 {
+        u32::MAX >> (32 - self.size())
+    }
+
+    /// Returns the indices of the fields in a row, column or box; first all
+    /// rows, then all columns, then all boxes
+    fn house(self, house: usize) -> impl Iterator<Item = usize> {
+        let size = self.size();
+        let (kind, i) = (house / size, house % size);
+        let (box_x, box_y) = (
+            i % self.box_height() * self.box_width(),
+            i / self.box_height() * self.box_height(),
+        );
+        (0..size).map(move |j| match kind {
+            0 => i * size + j,
+            1 => j * size + i,
+            _ => (box_y + j / self.box_width()) * size + box_x + j % self.box_width(),
+        })
+    }
+
+    /// Returns the indices of all fields in the same row, column or box as
+    /// the field at `index`, possibly more than once, excluding `index`
+    fn peers(self, index: usize) -> impl Iterator<Item = usize> {
+        let size = self.size();
+        let (x, y) = (index % size, index / size);
+        let box_index = y / self.box_height() * self.box_height() + x / self.box_width();
+        self.house(y)
+            .chain(self.house(size + x))
+            .chain(self.house(2 * size + box_index))
+            .filter(move |&i| i != index)
+    }
+}
+
+/// A sudoku grid of any [Geometry], where each cell can contain multiple
+/// possibilities. Works like [Grid], but numbers are represented as `u8`s.
+///
+/// Numbers above 9 are written as letters, starting with `A` for 10.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Geometry, SizedGrid, SolutionCount};
+///
+/// let grid = SizedGrid::parse(Geometry::SIZE_4, "
+///     1 . | . .
+///     . . | . 2
+///     ----+----
+///     . 1 | . .
+///     . . | 3 .
+/// ").unwrap();
+/// assert_eq!(grid.count_solutions(2), SolutionCount::Exact(1));
+///
+/// let mut acc = Vec::new();
+/// grid.solve(&mut acc, Default::default());
+/// assert_eq!(acc[0].value(3, 0), Some(3));
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SizedGrid {
+    geometry: Geometry,
+    fields: Vec<u32>,
+}
+
+impl SizedGrid {
+    /// Creates a grid from the numbers of all fields, row by row, where `0`
+    /// is an unsolved field, and fills out all possibilities correctly.
+    ///
+    /// Panics if the number of values is not `size * size`, or if a value
+    /// is larger than `size`.
+    pub fn new(geometry: Geometry, values: &[u8]) -> Self {
+        let size = geometry.size();
+        assert_eq!(values.len(), size * size, "Invalid number of values");
+
+        let mut grid = SizedGrid::full(geometry);
+        for (i, &n) in values.iter().enumerate() {
+            if n != 0 {
+                grid.set(i % size, i / size, n);
+            }
+        }
+        grid
+    }
+
+    /// Creates an unconstrained grid (no number is known, so all
+    /// possibilities are filled out)
+    pub fn full(geometry: Geometry) -> Self {
+        SizedGrid {
+            geometry,
+            fields: vec![geometry.mask(); geometry.size() * geometry.size()],
+        }
+    }
+
+    /// Parses a grid, where `1` to `9` and `A` to `W` (case-insensitive) are
+    /// solved fields, and `.`, `0` and `_` are unsolved fields. Whitespace
+    /// and the characters `|-+` are ignored.
+    pub fn parse(geometry: Geometry, s: &str) -> Result<Self, ParseError> {
+        let size = geometry.size();
+        let mut values = Vec::with_capacity(size * size);
+        for (li, line) in s.lines().enumerate() {
+            for c in line.chars() {
+                let err = || ParseError::new(li + 1, ParseErrorKind::InvalidChar(c));
+                if c.is_whitespace() || "|-+".contains(c) {
+                    continue;
+                } else if ".0_".contains(c) {
+                    values.push(0);
+                } else {
+                    let n = c
+                        .to_digit(33)
+                        .filter(|&n| n as usize <= size)
+                        .ok_or_else(err)?;
+                    values.push(n as u8);
+                }
+            }
+        }
+
+        if values.len() != size * size {
+            return Err(ParseError::new(
+                s.lines().count(),
+                ParseErrorKind::InvalidLength {
+                    expected: size * size,
+                    found: values.len(),
+                },
+            ));
+        }
+        Ok(SizedGrid::new(geometry, &values))
+    }
+
+    /// Returns the geometry of the grid
+    pub fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    /// Converts the grid to a [Grid], if it is 9x9
+    pub fn to_grid(&self) -> Option<Grid> {
+        if self.geometry != Geometry::SIZE_9 {
+            return None;
+        }
+        let mut grid = Grid::full();
+        for pos in Pos::iterator() {
+            let field = self.fields[pos.y() as usize * 9 + pos.x() as usize];
+            grid[pos] = crate::Field::from_bits(field as u16);
+        }
+        Some(grid)
+    }
+
+    /// Returns the number in the field, if it is solved
+    pub fn value(&self, x: usize, y: usize) -> Option<u8> {
+        let field = self.fields[self.index(x, y)];
+        (field.count_ones() == 1).then(|| field.trailing_zeros() as u8 + 1)
+    }
+
+    /// Returns an iterator over the possibilities of the field
+    pub fn possibilities(&self, x: usize, y: usize) -> impl Iterator<Item = u8> + use<> {
+        let field = self.fields[self.index(x, y)];
+        (1..=32).filter(move |n| field & (1 << (n - 1)) != 0)
+    }
+
+    /// Set the field to the given number, update the surrounding fields
+    /// recursively, and return whether any updated field is now empty (has no
+    /// possible numbers). See [Grid::set].
+    ///
+    /// Panics if the position or number is out of range.
+    pub fn set(&mut self, x: usize, y: usize, n: u8) -> bool {
+        assert!(
+            n != 0 && n as usize <= self.geometry.size(),
+            "Invalid number"
+        );
+        let index = self.index(x, y);
+        self.set_index(index, 1 << (n - 1), &mut ())
+    }
+
+    fn set_index(&mut self, index: usize, bit: u32, stats: &mut impl Stats) -> bool {
+        stats.propagate();
+        if stats.is_interrupted() {
+            return true;
+        }
+        self.fields[index] = bit;
+        for peer in self.geometry.peers(index) {
+            let field = &mut self.fields[peer];
+            if *field == bit {
+                // field would be empty after removing the number
+                return true;
+            } else if *field & bit != 0 {
+                *field &= !bit;
+                if field.count_ones() == 1 {
+                    let field = *field;
+                    if self.set_index(peer, field, stats) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Sets all numbers that can only go in one field of a row, column or box,
+    /// and returns whether the grid has a contradiction
+    fn set_hidden_singles(&mut self, stats: &mut impl Stats) -> bool {
+        let size = self.geometry.size();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for house in 0..3 * size {
+                let (mut once, mut twice) = (0, 0);
+                for i in self.geometry.house(house) {
+                    twice |= once & self.fields[i];
+                    once |= self.fields[i];
+                }
+                if once != self.geometry.mask() {
+                    return true;
+                }
+
+                let mut singles = once & !twice;
+                while singles != 0 {
+                    let bit = singles & singles.wrapping_neg();
+                    singles &= !bit;
+                    // setting an earlier single may have removed this one
+                    let Some(i) = self
+                        .geometry
+                        .house(house)
+                        .find(|&i| self.fields[i] & bit != 0)
+                    else {
+                        return true;
+                    };
+                    if self.fields[i] != bit {
+                        if self.set_index(i, bit, stats) {
+                            return true;
+                        }
+                        changed = true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the position `(x, y)` of an unsolved field with the fewest
+    /// possibilities, or `None` if the grid is solved
+    pub fn best_field_to_guess(&self) -> Option<(usize, usize)> {
+        let size = self.geometry.size();
+        let index = SearchGrid::best_field_to_guess(self)?;
+        Some((index % size, index / size))
+    }
+
+    /// Solves the grid, putting all discovered solutions in the `acc`
+    /// vector, see [solve](crate::solve)
+    pub fn solve(&self, acc: &mut Vec<SizedGrid>, options: SolveOptions) -> SolveStatus {
+        for_each_solution(self, None, &options, |solution| {
+            acc.push(solution.clone());
+            acc.len() < options.max_solutions
+        })
+    }
+
+    /// Like [SizedGrid::solve], but also returns statistics about the search
+    pub fn solve_with_stats(
+        &self,
+        acc: &mut Vec<SizedGrid>,
+        options: SolveOptions,
+    ) -> (SolveStatus, SolveStats) {
+        search_with_stats(self, acc, options)
+    }
+
+    /// Counts the solutions of the grid, and stops as soon as `limit`
+    /// solutions were found, see [count_solutions](crate::count_solutions)
+    pub fn count_solutions(&self, limit: u64) -> SolutionCount {
+        count(self, None, limit)
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        let size = self.geometry.size();
+        assert!(x < size && y < size, "Invalid position");
+        y * size + x
+    }
+}
+
+/// Generates a full sudoku grid with the given geometry, where every possible
+/// grid can occur, see [generate](crate::generate).
+///
+/// Large grids sometimes run into a dead end that takes very long to search,
+/// so the search is restarted after too many guesses.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::Geometry;
+///
+/// let grid = sudoku::generate_sized(Geometry::SIZE_16, &mut rand::rng());
+/// assert_eq!(grid.best_field_to_guess(), None);
+/// ```
+pub fn generate_sized(geometry: Geometry, rng: &mut impl rand::Rng) -> SizedGrid {
+    let max_guesses = 16 * geometry.size() as u64 * geometry.size() as u64;
+    let full = SizedGrid::full(geometry);
+    loop {
+        let random_index = &mut |len| rng.random_range(..len);
+        if let Some(grid) = random_solution(&full, None, max_guesses, random_index) {
+            return grid;
+        }
+    }
+}
+
+impl SearchGrid for SizedGrid {
+    /// The index of the field
+    type Pos = usize;
+    /// The bit of the number
+    type Num = u32;
+
+    fn best_field_to_guess(&self) -> Option<usize> {
+        let (index, _) = self
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| field.count_ones() > 1)
+            .min_by_key(|(_, field)| field.count_ones())?;
+        Some(index)
+    }
+
+    fn possibilities(&self, index: usize) -> impl Iterator<Item = u32> {
+        let field = self.fields[index];
+        (0..32).map(|i| 1 << i).filter(move |bit| field & bit != 0)
+    }
+
+    fn prepare(&mut self, _: Option<&Variant>, stats: &mut impl Stats) -> bool {
+        self.set_hidden_singles(stats)
+    }
+
+    fn place(
+        &mut self,
+        _: &SizedGrid,
+        index: usize,
+        bit: u32,
+        _: Option<&Variant>,
+        stats: &mut impl Stats,
+    ) -> bool {
+        self.set_index(index, bit, stats) || self.set_hidden_singles(stats)
+    }
+
+    fn is_valid(&self, _: Option<&Variant>) -> bool {
+        true
+    }
+}
+
+impl From<&Grid> for SizedGrid {
+    fn from(grid: &Grid) -> Self {
+        SizedGrid {
+            geometry: Geometry::SIZE_9,
+            fields: grid
+                .fields
+                .iter()
+                .flatten()
+                .map(|field| field.bits().into())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for SizedGrid {
+    /// Writes the numbers of the grid, one row per line, with `.` for unsolved
+    /// fields
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = self.geometry.size();
+        for y in 0..size {
+            for x in 0..size {
+                match self.value(x, y) {
+                    Some(n) if n <= 9 => write!(f, "{}", Number::new(n).as_str())?,
+                    Some(n) => write!(f, "{}", (b'A' + n - 10) as char)?,
+                    None => f.write_str(".")?,
+                }
+            }
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SizedGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[test]
+fn test_sizes() {
+    let mut rng = rand::rng();
+    for geometry in [
+        Geometry::SIZE_4,
+        Geometry::SIZE_6,
+        Geometry::SIZE_9,
+        Geometry::SIZE_12,
+        Geometry::SIZE_16,
+        Geometry::SIZE_25,
+    ] {
+        let size = geometry.size();
+        let grid = generate_sized(geometry, &mut rng);
+        for house in 0..3 * size {
+            let numbers: u32 = geometry
+                .house(house)
+                .map(|i| grid.fields[i])
+                .fold(0, |a, b| a | b);
+            assert_eq!(numbers, geometry.mask());
+        }
+        assert_eq!(SizedGrid::parse(geometry, &grid.to_string()), Ok(grid));
+    }
+}
+
+#[test]
+fn test_count_solutions() {
+    // there are 288 4x4 grids
+    assert_eq!(
+        SizedGrid::full(Geometry::SIZE_4).count_solutions(1000),
+        SolutionCount::Exact(288)
+    );
+
+    let grid = crate::generate(&mut rand::rng());
+    let sized = SizedGrid::from(&grid);
+    assert_eq!(sized.to_grid(), Some(grid));
+    assert_eq!(sized.count_solutions(2), SolutionCount::Exact(1));
+
+    let grid = SizedGrid::parse(
+        Geometry::SIZE_6,
+        "12345. ...... ...... ...... ...... ......",
+    )
+    .unwrap();
+    assert_eq!(grid.value(5, 0), Some(6));
+
+    let mut acc = Vec::new();
+    let options = SolveOptions::with_max_solutions(usize::MAX).with_max_guesses(10);
+    let (status, stats) = SizedGrid::full(Geometry::SIZE_16).solve_with_stats(&mut acc, options);
+    assert_eq!(status, SolveStatus::GuessLimitReached);
+    assert_eq!(stats.guesses, 10);
+}
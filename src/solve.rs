@@ -1,17 +1,221 @@
-use crate::Grid;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::{Grid, Number, Variant};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 #[non_exhaustive]
 pub struct SolveOptions {
     /// Number of solutions after which we can stop looking for more.
-    /// Due to the recursive implementation, the function may return more
-    /// solutions than asked.
     pub max_solutions: usize,
+    /// Point in time after which the search is interrupted. It is only
+    /// checked periodically, so the search may run slightly longer.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub deadline: Option<Instant>,
+    /// Maximum number of guesses (numbers tried in a field with several
+    /// possibilities) after which the search is interrupted.
+    pub max_guesses: Option<u64>,
+    /// The search is interrupted as soon as this is cancelled, e.g. from
+    /// another thread.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cancel: Option<CancelToken>,
 }
 
 impl SolveOptions {
     pub fn with_max_solutions(max_solutions: usize) -> Self {
-        SolveOptions { max_solutions }
+        SolveOptions {
+            max_solutions,
+            ..SolveOptions::default()
+        }
+    }
+
+    /// Interrupts the search after the given point in time
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Interrupts the search after the given time, measured from now
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Interrupts the search after the given number of guesses
+    pub fn with_max_guesses(mut self, max_guesses: u64) -> Self {
+        self.max_guesses = Some(max_guesses);
+        self
+    }
+
+    /// Interrupts the search as soon as `cancel` is cancelled
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            max_solutions: 1,
+            deadline: None,
+            max_guesses: None,
+            cancel: None,
+        }
+    }
+}
+
+/// A flag to cancel searches, e.g. from another thread. Clones share the
+/// same flag, and are equal to each other.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{CancelToken, Grid, SolveOptions, SolveStatus, solve};
+///
+/// let cancel = CancelToken::new();
+/// let options = SolveOptions::default().with_cancel(cancel.clone());
+/// cancel.cancel();
+///
+/// let status = solve(&Grid::full(), &mut Vec::new(), options);
+/// assert_eq!(status, SolveStatus::Cancelled);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// Cancels all searches using this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [CancelToken::cancel] was called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancelToken {}
+
+/// Whether [solve] explored the search space as far as requested, or was
+/// interrupted by one of the limits in [SolveOptions].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveStatus {
+    /// All solutions were found, or `max_solutions` was reached
+    Finished,
+    /// The deadline has passed
+    TimedOut,
+    /// The maximum number of guesses was reached
+    GuessLimitReached,
+    /// The search was cancelled
+    Cancelled,
+}
+
+impl SolveStatus {
+    /// Whether the search finished without being interrupted
+    pub fn is_finished(self) -> bool {
+        self == SolveStatus::Finished
+    }
+}
+
+/// Statistics about a search, returned by [solve_with_stats].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// Number of guesses, i.e. numbers tried in a field with several
+    /// possibilities
+    pub guesses: u64,
+    /// Number of guesses that didn't lead to a solution
+    pub backtracks: u64,
+    /// Maximum number of nested guesses
+    pub max_depth: u32,
+    /// Number of calls to [Grid::set], including recursive calls
+    pub propagations: u64,
+    /// Time spent searching
+    pub duration: Duration,
+}
+
+/// Records statistics during a search. The implementation for `()` does
+/// nothing, so searching without statistics has no overhead.
+pub(crate) trait Stats {
+    fn backtrack(&mut self);
+    fn depth(&mut self, depth: u32);
+    fn propagate(&mut self);
+
+    /// Whether the search should stop, which is checked after every call to
+    /// [Stats::propagate]
+    #[inline(always)]
+    fn is_interrupted(&self) -> bool {
+        false
+    }
+}
+
+impl Stats for () {
+    #[inline(always)]
+    fn backtrack(&mut self) {}
+    #[inline(always)]
+    fn depth(&mut self, _: u32) {}
+    #[inline(always)]
+    fn propagate(&mut self) {}
+}
+
+/// Checks the deadline of a search while propagating, and passes everything
+/// else on to the inner statistics
+struct Deadline<S> {
+    stats: S,
+    deadline: Option<Instant>,
+    propagations: u32,
+    passed: bool,
+}
+
+impl<S: Stats> Stats for Deadline<S> {
+    fn backtrack(&mut self) {
+        self.stats.backtrack();
+    }
+
+    fn depth(&mut self, depth: u32) {
+        self.stats.depth(depth);
+    }
+
+    fn propagate(&mut self) {
+        self.stats.propagate();
+        self.propagations = self.propagations.wrapping_add(1);
+        // checking the time is comparatively expensive, so it's only done
+        // every 256 propagations
+        if self.propagations.is_multiple_of(256)
+            && self.deadline.is_some_and(|d| Instant::now() >= d)
+        {
+            self.passed = true;
+        }
+    }
+
+    fn is_interrupted(&self) -> bool {
+        self.passed
+    }
+}
+
+impl Stats for SolveStats {
+    fn backtrack(&mut self) {
+        self.backtracks += 1;
+    }
+
+    fn depth(&mut self, depth: u32) {
+        self.max_depth = self.max_depth.max(depth);
+    }
+
+    fn propagate(&mut self) {
+        self.propagations += 1;
     }
 }
 
@@ -21,28 +225,545 @@ impl SolveOptions {
 /// By default, only the first solution is provided, after which we stop looking
 /// for more. This behaviour can be changed by setting the `max_solutions` field
 /// in [SolveOptions].
-pub fn solve(grid: &Grid, acc: &mut Vec<Grid>, options: SolveOptions) {
-    brute_force(grid, acc, options.max_solutions);
+///
+/// The search can be limited with a deadline, a maximum number of guesses, or
+/// a cancellation flag. If it is interrupted, `acc` contains the solutions
+/// found so far, and the returned [SolveStatus] tells why it stopped.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Grid, SolveOptions, SolveStatus, solve};
+///
+/// let mut acc = Vec::new();
+/// let options = SolveOptions::with_max_solutions(usize::MAX).with_max_guesses(1000);
+/// let status = solve(&Grid::full(), &mut acc, options);
+///
+/// assert_eq!(status, SolveStatus::GuessLimitReached);
+/// assert!(!acc.is_empty());
+/// ```
+pub fn solve(grid: &Grid, acc: &mut Vec<Grid>, options: SolveOptions) -> SolveStatus {
+    for_each_solution(grid, None, &options, |solution| {
+        acc.push(solution.clone());
+        acc.len() < options.max_solutions
+    })
+}
+
+/// Solves the sudoku grid like [solve], but under the rules of `variant`.
+/// The grid should be created with [Variant::new_grid].
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Grid, SolveOptions, Variant, solve_with_variant};
+///
+/// let variant = Variant::sudoku_x();
+/// let mut acc = Vec::new();
+/// solve_with_variant(&Grid::full(), &variant, &mut acc, SolveOptions::default());
+///
+/// assert!(variant.is_valid(&acc[0]));
+/// ```
+pub fn solve_with_variant(
+    grid: &Grid,
+    variant: &Variant,
+    acc: &mut Vec<Grid>,
+    options: SolveOptions,
+) -> SolveStatus {
+    for_each_solution(grid, Some(variant), &options, |solution| {
+        acc.push(solution.clone());
+        acc.len() < options.max_solutions
+    })
+}
+
+/// Calls `on_solution` for every solution under the rules of `variant`, until
+/// it returns `false` or one of the limits in `options` is reached.
+/// `max_solutions` is ignored.
+pub(crate) fn for_each_solution<G: SearchGrid>(
+    grid: &G,
+    variant: Option<&Variant>,
+    options: &SolveOptions,
+    mut on_solution: impl FnMut(&G) -> bool,
+) -> SolveStatus {
+    let mut search = Search::new(options, variant, ());
+    search.start(grid, &mut on_solution);
+    search.status
+}
+
+/// Like [solve], but also returns statistics about the search.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Grid, SolveOptions, solve_with_stats};
+///
+/// let mut acc = Vec::new();
+/// let (status, stats) = solve_with_stats(&Grid::full(), &mut acc, SolveOptions::default());
+///
+/// assert!(status.is_finished());
+/// assert!(stats.guesses >= stats.max_depth as u64);
+/// assert!(stats.propagations >= 81);
+/// ```
+pub fn solve_with_stats(
+    grid: &Grid,
+    acc: &mut Vec<Grid>,
+    options: SolveOptions,
+) -> (SolveStatus, SolveStats) {
+    search_with_stats(grid, acc, options)
+}
+
+/// Implements [solve_with_stats] for any kind of grid
+pub(crate) fn search_with_stats<G: SearchGrid>(
+    grid: &G,
+    acc: &mut Vec<G>,
+    options: SolveOptions,
+) -> (SolveStatus, SolveStats) {
+    let start = Instant::now();
+    let mut search = Search::new(&options, None, SolveStats::default());
+    search.start(grid, &mut |solution| {
+        acc.push(solution.clone());
+        acc.len() < options.max_solutions
+    });
+
+    let mut stats = search.stats.stats;
+    stats.guesses = search.guesses;
+    stats.duration = start.elapsed();
+    (search.status, stats)
 }
 
-pub(super) fn brute_force(grid: &Grid, acc: &mut Vec<Grid>, max_solutions: usize) {
-    let Some(pos) = grid.best_field_to_guess() else {
-        acc.push(grid.clone());
-        return;
-    };
+/// Returns an iterator over all solutions of the sudoku grid. Solutions are
+/// computed lazily, so the search stops when the iterator is dropped.
+///
+/// Unlike [solve], this uses an explicit stack instead of recursion.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Grid, solutions};
+///
+/// let first_ten: Vec<Grid> = solutions(&Grid::full()).take(10).collect();
+/// assert_eq!(first_ten.len(), 10);
+/// ```
+pub fn solutions(grid: &Grid) -> impl Iterator<Item = Grid> + use<> {
+    let mut stack = vec![grid.clone()];
+    std::iter::from_fn(move || {
+        while let Some(grid) = stack.pop() {
+            let Some(pos) = grid.best_field_to_guess() else {
+                return Some(grid);
+            };
+
+            // push in reverse, so the smallest number is tried first
+            let mut nums = [Number::N1; 9];
+            let mut len = 0;
+            for num in grid[pos].possibilities() {
+                nums[len] = num;
+                len += 1;
+            }
+            for &num in nums[..len].iter().rev() {
+                let mut copy = grid.clone();
+                if !copy.set(pos, num) {
+                    stack.push(copy);
+                }
+            }
+        }
+        None
+    })
+}
+
+/// The number of solutions of a sudoku grid, see [count_solutions].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolutionCount {
+    /// The sudoku has exactly this many solutions
+    Exact(u64),
+    /// The sudoku has at least this many solutions; the limit was reached
+    AtLeast(u64),
+}
+
+impl SolutionCount {
+    /// Whether the sudoku has exactly one solution
+    pub fn is_unique(self) -> bool {
+        self == SolutionCount::Exact(1)
+    }
+}
+
+/// Counts the solutions of the sudoku grid without storing them, and stops
+/// as soon as `limit` solutions were found.
+///
+/// If fewer than `limit` solutions exist, `Exact(n)` is returned; otherwise,
+/// `AtLeast(limit)`. For example, to check if a sudoku is uniquely solvable,
+/// use a limit of 2:
+///
+/// ```
+/// use sudoku::{Grid, SolutionCount, count_solutions};
+///
+/// assert_eq!(count_solutions(&Grid::full(), 2), SolutionCount::AtLeast(2));
+///
+/// let grid = sudoku::generate(&mut rand::rng());
+/// assert_eq!(count_solutions(&grid, 2), SolutionCount::Exact(1));
+/// ```
+pub fn count_solutions(grid: &Grid, limit: u64) -> SolutionCount {
+    count(grid, None, limit)
+}
+
+/// Counts the solutions of the sudoku grid like [count_solutions], but under
+/// the rules of `variant`. The grid should be created with
+/// [Variant::new_grid].
+pub fn count_solutions_with_variant(grid: &Grid, variant: &Variant, limit: u64) -> SolutionCount {
+    count(grid, Some(variant), limit)
+}
+
+/// Implements [count_solutions] for any kind of grid, under the rules of
+/// `variant`
+pub(crate) fn count<G: SearchGrid>(
+    grid: &G,
+    variant: Option<&Variant>,
+    limit: u64,
+) -> SolutionCount {
+    let mut count = 0;
+    if limit > 0 {
+        Search::new(&SolveOptions::default(), variant, ()).start(grid, &mut |_| {
+            count += 1;
+            count < limit
+        });
+    }
+
+    if count < limit {
+        SolutionCount::Exact(count)
+    } else {
+        SolutionCount::AtLeast(limit)
+    }
+}
+
+pub(super) fn brute_force(
+    grid: &Grid,
+    variant: &Variant,
+    acc: &mut Vec<Grid>,
+    max_solutions: usize,
+) {
+    Search::new(&SolveOptions::default(), Some(variant), ()).start(grid, &mut |solution| {
+        acc.push(solution.clone());
+        acc.len() < max_solutions
+    });
+}
+
+/// Returns the first solution found by guessing numbers in random order, or
+/// `None` if there is none or `max_guesses` guesses were not enough.
+/// `random_index(len)` returns a random index below `len`.
+pub(crate) fn random_solution<G: SearchGrid>(
+    grid: &G,
+    variant: Option<&Variant>,
+    max_guesses: u64,
+    random_index: &mut impl FnMut(usize) -> usize,
+) -> Option<G> {
+    let options = SolveOptions::default().with_max_guesses(max_guesses);
+    let mut search = Search::new(&options, variant, ());
+    search.random_index = Some(random_index);
+    let mut solution = None;
+    search.start(grid, &mut |grid| {
+        solution = Some(grid.clone());
+        false
+    });
+    solution
+}
+
+/// A grid that can be solved by [Search]. This is implemented for [Grid] and
+/// [SizedGrid](crate::SizedGrid), which only supports the classic rules, so
+/// it ignores the variant.
+pub(crate) trait SearchGrid: Clone {
+    type Pos: Copy;
+    type Num: Copy;
+
+    /// Returns an unsolved field with the fewest possibilities, or `None` if
+    /// the grid is solved
+    fn best_field_to_guess(&self) -> Option<Self::Pos>;
+
+    /// Returns the possibilities of a field
+    fn possibilities(&self, pos: Self::Pos) -> impl Iterator<Item = Self::Num>;
+
+    /// Rules out all possibilities that contradict the rules before the
+    /// search starts. Returns whether a field is now empty.
+    fn prepare(&mut self, variant: Option<&Variant>, stats: &mut impl Stats) -> bool;
+
+    /// Sets the field to the number, and rules out all possibilities that
+    /// contradict the rules. `before` is the grid before the last guess.
+    /// Returns whether a field is now empty.
+    fn place(
+        &mut self,
+        before: &Self,
+        pos: Self::Pos,
+        num: Self::Num,
+        variant: Option<&Variant>,
+        stats: &mut impl Stats,
+    ) -> bool;
+
+    /// Returns whether a solved grid follows the rules
+    fn is_valid(&self, variant: Option<&Variant>) -> bool;
+}
+
+impl SearchGrid for Grid {
+    type Pos = crate::Pos;
+    type Num = Number;
+
+    fn best_field_to_guess(&self) -> Option<crate::Pos> {
+        Grid::best_field_to_guess(self)
+    }
+
+    fn possibilities(&self, pos: crate::Pos) -> impl Iterator<Item = Number> {
+        self[pos].possibilities()
+    }
+
+    fn prepare(&mut self, variant: Option<&Variant>, stats: &mut impl Stats) -> bool {
+        match variant {
+            None => false,
+            // the solved fields may have been set without the regions
+            Some(variant) if variant.regions().is_some() => variant.place_solved(self, stats),
+            Some(variant) => variant.propagate(self, &Grid::full(), stats),
+        }
+    }
+
+    fn place(
+        &mut self,
+        before: &Grid,
+        pos: crate::Pos,
+        num: Number,
+        variant: Option<&Variant>,
+        stats: &mut impl Stats,
+    ) -> bool {
+        match variant {
+            None => self.set_with_stats(pos, num, stats),
+            Some(variant) => {
+                variant.place(self, pos, num, stats) || variant.propagate(self, before, stats)
+            }
+        }
+    }
+
+    fn is_valid(&self, variant: Option<&Variant>) -> bool {
+        variant.is_none_or(|v| v.is_valid(self))
+    }
+}
+
+/// A depth-first search for solutions, which stops when one of its limits is
+/// reached
+struct Search<'a, S> {
+    max_guesses: u64,
+    cancel: Option<CancelToken>,
+    variant: Option<&'a Variant>,
+    /// Picks the order of the possibilities; `None` tries them in order
+    random_index: Option<&'a mut dyn FnMut(usize) -> usize>,
+    guesses: u64,
+    solutions: u64,
+    status: SolveStatus,
+    stats: Deadline<S>,
+}
+
+impl<'a, S: Stats> Search<'a, S> {
+    fn new(options: &SolveOptions, variant: Option<&'a Variant>, stats: S) -> Self {
+        Search {
+            max_guesses: options.max_guesses.unwrap_or(u64::MAX),
+            cancel: options.cancel.clone(),
+            variant: variant.filter(|v| !v.is_classic()),
+            random_index: None,
+            guesses: 0,
+            solutions: 0,
+            status: SolveStatus::Finished,
+            stats: Deadline {
+                stats,
+                deadline: options.deadline,
+                propagations: 0,
+                passed: false,
+            },
+        }
+    }
+
+    /// Rules out possibilities that contradict the rules, then calls
+    /// `on_solution` for every solution like [Search::run]
+    fn start<G: SearchGrid>(&mut self, grid: &G, on_solution: &mut impl FnMut(&G) -> bool) -> bool {
+        let mut grid = grid.clone();
+        if grid.prepare(self.variant, &mut self.stats) {
+            return !self.is_timed_out();
+        }
+        self.run(&grid, 0, on_solution)
+    }
+
+    /// Calls `on_solution` for every solution, until it returns `false` or a
+    /// limit is reached. Returns `false` if the search was stopped. `depth` is
+    /// the number of guesses that led to `grid`.
+    fn run<G: SearchGrid>(
+        &mut self,
+        grid: &G,
+        depth: u32,
+        on_solution: &mut impl FnMut(&G) -> bool,
+    ) -> bool {
+        self.stats.depth(depth);
+        let Some(pos) = grid.best_field_to_guess() else {
+            if !grid.is_valid(self.variant) {
+                return true;
+            }
+            self.solutions += 1;
+            return on_solution(grid);
+        };
+
+        if self.random_index.is_none() {
+            for num in grid.possibilities(pos) {
+                if !self.try_number(grid, pos, num, depth, on_solution) {
+                    return false;
+                }
+            }
+            return true;
+        }
+
+        let mut nums: Vec<G::Num> = grid.possibilities(pos).collect();
+        while let Some(random_index) = self.random_index.as_mut()
+            && !nums.is_empty()
+        {
+            let num = nums.swap_remove(random_index(nums.len()));
+            if !self.try_number(grid, pos, num, depth, on_solution) {
+                return false;
+            }
+        }
+        true
+    }
 
-    let field = grid[pos];
+    /// Guesses the number in the field and searches for solutions like
+    /// [Search::run]
+    #[inline(always)]
+    fn try_number<G: SearchGrid>(
+        &mut self,
+        grid: &G,
+        pos: G::Pos,
+        num: G::Num,
+        depth: u32,
+        on_solution: &mut impl FnMut(&G) -> bool,
+    ) -> bool {
+        if !self.guess() {
+            return false;
+        }
 
-    for num in field.possibilities() {
+        let solutions = self.solutions;
         let mut copy = grid.clone();
+        if copy.place(grid, pos, num, self.variant, &mut self.stats) {
+            if self.is_timed_out() {
+                return false;
+            }
+        } else if !self.run(&copy, depth + 1, on_solution) {
+            return false;
+        }
+        if self.solutions == solutions {
+            self.stats.backtrack();
+        }
+        true
+    }
 
-        let is_empty = copy.set(pos, num);
-        if is_empty {
-            continue;
+    /// Returns whether the search may continue with another guess, and
+    /// counts it if so
+    fn guess(&mut self) -> bool {
+        if self.guesses >= self.max_guesses {
+            self.status = SolveStatus::GuessLimitReached;
+        } else if self.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+            self.status = SolveStatus::Cancelled;
+        } else {
+            self.guesses += 1;
         }
-        brute_force(&copy, acc, max_solutions);
-        if acc.len() >= max_solutions {
-            break;
+        self.status.is_finished()
+    }
+
+    /// Whether the deadline passed during propagation, which then stopped
+    /// early. Updates the status accordingly.
+    fn is_timed_out(&mut self) -> bool {
+        if self.stats.is_interrupted() {
+            self.status = SolveStatus::TimedOut;
         }
+        self.stats.is_interrupted()
     }
 }
+
+#[test]
+fn test_count_solutions() {
+    let grid = Grid::new([
+        crate::row![_ 8 _  _ _ _  _ _ _],
+        crate::row![_ 6 _  _ _ 5  3 _ _],
+        crate::row![_ _ _  _ 9 _  5 6 _],
+        crate::row![_ _ _  _ _ _  8 _ 2],
+        crate::row![_ _ _  _ _ _  _ 4 _],
+        crate::row![3 _ 7  _ 2 _  _ _ _],
+        crate::row![_ _ 5  _ 6 _  9 8 _],
+        crate::row![7 _ _  4 _ _  _ _ 3],
+        crate::row![_ 4 _  _ _ 1  _ _ _],
+    ]);
+    let count = count_solutions(&grid, 1000);
+    let mut acc = Vec::new();
+    solve(&grid, &mut acc, SolveOptions::with_max_solutions(1000));
+    assert_eq!(count, SolutionCount::Exact(acc.len() as u64));
+
+    assert_eq!(count_solutions(&Grid::full(), 0), SolutionCount::AtLeast(0));
+    assert_eq!(
+        count_solutions(&Grid::full(), 50),
+        SolutionCount::AtLeast(50)
+    );
+}
+
+#[test]
+fn test_solutions() {
+    let mut grid = Grid::full();
+    let first_row = [3, 1, 2, 6, 4, 5, 9, 7, 8];
+    for (x, n) in first_row.into_iter().enumerate() {
+        grid.set(crate::Pos::new(x as u8, 0), Number::new(n));
+    }
+
+    let mut acc = Vec::new();
+    solve(&grid, &mut acc, SolveOptions::with_max_solutions(200));
+    let lazy: Vec<Grid> = solutions(&grid).take(200).collect();
+    assert_eq!(acc, lazy);
+}
+
+#[test]
+fn test_limits() {
+    let mut acc = Vec::new();
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let options = SolveOptions::with_max_solutions(10).with_cancel(cancel);
+    assert_eq!(
+        solve(&Grid::full(), &mut acc, options),
+        SolveStatus::Cancelled
+    );
+    assert!(acc.is_empty());
+
+    let options = SolveOptions::with_max_solutions(usize::MAX).with_deadline(Instant::now());
+    assert_eq!(
+        solve(&Grid::full(), &mut acc, options),
+        SolveStatus::TimedOut
+    );
+
+    acc.clear();
+    let options = SolveOptions::with_max_solutions(10).with_max_guesses(1_000_000);
+    assert_eq!(
+        solve(&Grid::full(), &mut acc, options),
+        SolveStatus::Finished
+    );
+    assert_eq!(acc.len(), 10);
+}
+
+#[test]
+fn test_stats() {
+    let options = SolveOptions::with_max_solutions(usize::MAX).with_max_guesses(100);
+    let (status, stats) = solve_with_stats(&Grid::full(), &mut Vec::new(), options);
+    assert_eq!(status, SolveStatus::GuessLimitReached);
+    assert_eq!(stats.guesses, 100);
+
+    // the 4 in the top left corner makes this unsolvable, but that is only
+    // found out by guessing, so every guess is a backtrack
+    let grid = Grid::new([
+        crate::row![4 _ 1  _ 2 _  _ 8 _],
+        crate::row![_ 5 9  _ 6 _  _ _ _],
+        crate::row![6 _ _  5 _ _  3 _ _],
+        crate::row![_ _ _  6 _ _  _ _ _],
+        crate::row![2 _ _  _ _ _  _ _ 7],
+        crate::row![_ 9 _  1 _ _  _ _ _],
+        crate::row![_ _ _  _ 5 4  8 3 _],
+        crate::row![3 _ _  _ 8 _  _ 7 _],
+        crate::row![_ 2 _  _ _ _  9 4 _],
+    ]);
+    let (status, stats) = solve_with_stats(&grid, &mut Vec::new(), SolveOptions::default());
+    assert!(status.is_finished());
+    assert!(stats.guesses > 0);
+    assert_eq!(stats.backtracks, stats.guesses);
+}
@@ -0,0 +1,281 @@
+use std::io::{self, BufRead, Write};
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::formats::{Puzzle, format_line, parse_line};
+use crate::{Grid, Restrictions, restrictions};
+
+/// Reads puzzles from a collection with one puzzle per line, without loading
+/// the whole collection into memory.
+///
+/// Each line starts with 81 fields, where `1` to `9` are solved fields and
+/// `.`, `0` and `_` are unsolved fields. The puzzle may be followed by a
+/// comment or rating, separated by whitespace or one of the characters
+/// `#;,|`. Empty lines and lines starting with `#` or `;` are skipped.
+///
+/// After a space, the puzzle may be followed by its [Restrictions] as 81
+/// fields written like in [Puzzle]'s [Display](std::fmt::Display), e.g. `E`
+/// for an even field, which are returned by [PuzzleReader::restrictions].
+///
+/// Lines that can't be parsed are returned as errors, and reading continues
+/// with the next line. Lines that aren't valid UTF-8 are returned as
+/// [ParseErrorKind::Io] errors of the kind
+/// [InvalidData](io::ErrorKind::InvalidData), and reading continues as well.
+/// Any other I/O error is returned once, after which the iterator ends.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::PuzzleReader;
+///
+/// let input = "\
+/// ; my collection
+/// 003020600900305001001806400008102900700000008006708200002609500800203009005010300 easy
+/// 123
+/// ";
+/// let mut reader = PuzzleReader::new(input.as_bytes());
+///
+/// assert!(reader.next().unwrap().is_ok());
+/// assert_eq!(reader.line(), 2);
+/// assert_eq!(reader.comment(), "easy");
+///
+/// assert_eq!(reader.next().unwrap().unwrap_err().line(), 3);
+/// assert!(reader.next().is_none());
+/// ```
+pub struct PuzzleReader<R> {
+    inner: R,
+    /// The bytes of the last line, which are copied to `buf` if they are
+    /// valid UTF-8
+    bytes: Vec<u8>,
+    buf: String,
+    line: usize,
+    comment_start: usize,
+    restrictions: Restrictions,
+    /// Set after an I/O error, to end the iteration
+    failed: bool,
+}
+
+impl<R: BufRead> PuzzleReader<R> {
+    /// Creates a reader that reads puzzles line by line from `inner`. Wrap
+    /// unbuffered readers such as files in a [BufReader](std::io::BufReader).
+    pub fn new(inner: R) -> Self {
+        PuzzleReader {
+            inner,
+            bytes: Vec::new(),
+            buf: String::new(),
+            line: 0,
+            comment_start: 0,
+            restrictions: Restrictions::new(),
+            failed: false,
+        }
+    }
+
+    /// Returns the line number (starting with 1) of the last line that was
+    /// read
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the comment or rating after the last puzzle that was read,
+    /// without the separator
+    pub fn comment(&self) -> &str {
+        self.buf[self.comment_start..]
+            .trim_start_matches(|c: char| c.is_whitespace() || "#;,|".contains(c))
+            .trim_end()
+    }
+
+    /// Returns the restrictions of the last puzzle that was read, which are
+    /// empty if the line doesn't contain any
+    pub fn restrictions(&self) -> &Restrictions {
+        &self.restrictions
+    }
+
+    /// Returns the underlying reader
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Iterator for PuzzleReader<R> {
+    type Item = Result<Grid, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            self.bytes.clear();
+            self.buf.clear();
+            self.comment_start = 0;
+            self.restrictions = Restrictions::new();
+            self.line += 1;
+            match self.inner.read_until(b'\n', &mut self.bytes) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(ParseError::new(
+                        self.line,
+                        ParseErrorKind::Io(e.kind()),
+                    )));
+                }
+            }
+            match std::str::from_utf8(&self.bytes) {
+                Ok(line) => self.buf.push_str(line),
+                Err(_) => {
+                    let kind = ParseErrorKind::Io(io::ErrorKind::InvalidData);
+                    return Some(Err(ParseError::new(self.line, kind)));
+                }
+            }
+
+            let line = self.buf.trim_end();
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+
+            let end = line
+                .find(|c: char| c.is_whitespace() || "#;,|".contains(c))
+                .unwrap_or(line.len());
+            self.comment_start = end;
+            if let Some(rest) = line[end..].strip_prefix(' ') {
+                let len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                if let Ok(restrictions) = restrictions::parse_line(&rest[..len]) {
+                    self.restrictions = restrictions;
+                    self.comment_start = end + 1 + len;
+                }
+            }
+            return Some(parse_line(&line[..end], self.line));
+        }
+    }
+}
+
+/// Writes puzzles to a collection with one puzzle per line, which can be read
+/// with [PuzzleReader]. Unsolved fields are written as `.`.
+pub struct PuzzleWriter<W> {
+    inner: W,
+}
+
+impl<W: Write> PuzzleWriter<W> {
+    /// Creates a writer that writes puzzles line by line to `inner`
+    pub fn new(inner: W) -> Self {
+        PuzzleWriter { inner }
+    }
+
+    /// Writes the solved fields of the grid as a line
+    pub fn write(&mut self, grid: &Grid) -> io::Result<()> {
+        writeln!(self.inner, "{}", format_line(grid, "."))
+    }
+
+    /// Writes the solved fields of the grid as a line, followed by a comment
+    /// or rating, which must not contain line breaks
+    pub fn write_with_comment(&mut self, grid: &Grid, comment: &str) -> io::Result<()> {
+        writeln!(self.inner, "{} {comment}", format_line(grid, "."))
+    }
+
+    /// Writes the solved fields and the restrictions of the puzzle as a line,
+    /// followed by the comment of its metadata with line breaks replaced by
+    /// spaces. Other metadata is not written.
+    pub fn write_puzzle(&mut self, puzzle: &Puzzle) -> io::Result<()> {
+        let mut line = format_line(&puzzle.grid, ".");
+        if !puzzle.restrictions.is_empty() {
+            line.push(' ');
+            line.push_str(&restrictions::format_line(&puzzle.restrictions));
+        }
+        if let Some(comment) = &puzzle.metadata.comment {
+            line.push(' ');
+            line.push_str(&comment.replace('\n', " "));
+        }
+        writeln!(self.inner, "{line}")
+    }
+
+    /// Flushes the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let mut rng = rand::rng();
+    let grids: Vec<Grid> = (0..10).map(|_| crate::generate(&mut rng)).collect();
+
+    let mut writer = PuzzleWriter::new(Vec::new());
+    for (i, grid) in grids.iter().enumerate() {
+        writer.write_with_comment(grid, &i.to_string()).unwrap();
+    }
+    let output = writer.into_inner();
+
+    let mut reader = PuzzleReader::new(output.as_slice());
+    for (i, grid) in grids.iter().enumerate() {
+        assert_eq!(reader.next(), Some(Ok(grid.clone())));
+        assert_eq!(reader.comment(), i.to_string());
+        assert_eq!(reader.line(), i + 1);
+    }
+    assert_eq!(reader.next(), None);
+}
+
+#[test]
+fn test_restrictions() {
+    use crate::formats::Metadata;
+    use crate::{Field, Pos};
+
+    let puzzle = Puzzle {
+        grid: crate::generate_from_seed(0),
+        restrictions: Restrictions::new()
+            .with_even(Pos::new(0, 0))
+            .with(Pos::new(8, 8), Field::from_bits(0b1011)),
+        metadata: Metadata {
+            comment: Some("two\nlines".to_string()),
+            ..Metadata::default()
+        },
+    };
+    let mut writer = PuzzleWriter::new(Vec::new());
+    writer.write_puzzle(&puzzle).unwrap();
+    writer.write(&puzzle.grid).unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(output.lines().next().unwrap().contains(" E....."));
+    assert!(output.contains("[124] two lines\n"));
+
+    let mut reader = PuzzleReader::new(output.as_bytes());
+    assert_eq!(reader.next(), Some(Ok(puzzle.grid.clone())));
+    assert_eq!(reader.restrictions(), &puzzle.restrictions);
+    assert_eq!(reader.comment(), "two lines");
+    assert_eq!(reader.next(), Some(Ok(puzzle.grid)));
+    assert!(reader.restrictions().is_empty());
+
+    // a comment that isn't a valid line of restrictions
+    let line = format!("{} E[12 hard", ".".repeat(81));
+    let mut reader = PuzzleReader::new(line.as_bytes());
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.restrictions().is_empty());
+    assert_eq!(reader.comment(), "E[12 hard");
+}
+
+#[test]
+fn test_io_error() {
+    struct Failing;
+
+    impl io::Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    let mut reader = PuzzleReader::new(io::BufReader::new(Failing));
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), &ParseErrorKind::Io(io::ErrorKind::BrokenPipe));
+    assert_eq!(reader.next(), None);
+
+    let mut input = b"\xff\n".to_vec();
+    input.extend(format_line(&crate::generate_from_seed(0), ".").bytes());
+    let mut reader = PuzzleReader::new(input.as_slice());
+    let err = reader.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), &ParseErrorKind::Io(io::ErrorKind::InvalidData));
+    assert_eq!(err.line(), 1);
+    assert!(reader.next().unwrap().is_ok());
+    assert_eq!(reader.line(), 2);
+}
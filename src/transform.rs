@@ -0,0 +1,363 @@
+use rand::seq::SliceRandom;
+
+use crate::{Field, Grid, Number, Pos};
+
+/// A validity-preserving symmetry of a sudoku grid.
+///
+/// A transform is made up of a relabeling of the numbers, a permutation of
+/// the rows (bands, and rows within a band), a permutation of the columns
+/// (stacks, and columns within a stack) and an optional transposition.
+/// Transforms can be composed with [Transform::then] and undone with
+/// [Transform::inverse].
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Field, Grid, Pos, Transform};
+///
+/// let mut grid = Grid::full();
+/// grid[Pos::new(0, 0)] = Field::new(1);
+///
+/// let transform = Transform::rotate_cw().then(Transform::swap_bands(0, 2));
+/// let disguised = transform.apply(&grid);
+/// assert_eq!(disguised[Pos::new(8, 6)], Field::new(1));
+///
+/// let restored = transform.inverse().apply(&disguised);
+/// assert_eq!(restored[Pos::new(0, 0)], Field::new(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform {
+    /// The number `n` is replaced with `digits[n - 1] + 1`
+    digits: [u8; 9],
+    /// Row `r` of the result is taken from row `rows[r]` of the (possibly
+    /// transposed) input
+    rows: [u8; 9],
+    /// Column `c` of the result is taken from column `cols[c]` of the
+    /// (possibly transposed) input
+    cols: [u8; 9],
+    /// Whether the input is transposed before permuting rows and columns
+    transpose: bool,
+}
+
+const IDENTITY: [u8; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+const REVERSED: [u8; 9] = [8, 7, 6, 5, 4, 3, 2, 1, 0];
+
+impl Transform {
+    /// The transform that doesn't change anything
+    pub fn identity() -> Self {
+        Transform {
+            digits: IDENTITY,
+            rows: IDENTITY,
+            cols: IDENTITY,
+            transpose: false,
+        }
+    }
+
+    /// Replaces every number `n` with `numbers[n - 1]`. Panics if `numbers`
+    /// contains a number twice.
+    pub fn relabel(numbers: [Number; 9]) -> Self {
+        let digits = numbers.map(|n| n.as_u8() - 1);
+        assert!(is_permutation(&digits), "{numbers:?} is not a permutation");
+        Transform {
+            digits,
+            ..Transform::identity()
+        }
+    }
+
+    /// Mirrors the grid along the main diagonal, so rows become columns
+    pub fn transpose() -> Self {
+        Transform {
+            transpose: true,
+            ..Transform::identity()
+        }
+    }
+
+    /// Rotates the grid by 90 degrees clockwise
+    pub fn rotate_cw() -> Self {
+        Transform {
+            cols: REVERSED,
+            transpose: true,
+            ..Transform::identity()
+        }
+    }
+
+    /// Rotates the grid by 180 degrees
+    pub fn rotate_180() -> Self {
+        Transform {
+            rows: REVERSED,
+            cols: REVERSED,
+            ..Transform::identity()
+        }
+    }
+
+    /// Rotates the grid by 90 degrees counter-clockwise
+    pub fn rotate_ccw() -> Self {
+        Transform {
+            rows: REVERSED,
+            transpose: true,
+            ..Transform::identity()
+        }
+    }
+
+    /// Reverses the order of the columns (mirrors along the vertical axis)
+    pub fn mirror_horizontal() -> Self {
+        Transform {
+            cols: REVERSED,
+            ..Transform::identity()
+        }
+    }
+
+    /// Reverses the order of the rows (mirrors along the horizontal axis)
+    pub fn mirror_vertical() -> Self {
+        Transform {
+            rows: REVERSED,
+            ..Transform::identity()
+        }
+    }
+
+    /// Swaps the rows `a` and `b` (between 0 and 2) within the given band
+    /// (between 0 and 2)
+    pub fn swap_rows(band: u8, a: u8, b: u8) -> Self {
+        Transform {
+            rows: swap_lines(band, a, b),
+            ..Transform::identity()
+        }
+    }
+
+    /// Swaps the bands (groups of 3 rows) `a` and `b`, which must be
+    /// between 0 and 2
+    pub fn swap_bands(a: u8, b: u8) -> Self {
+        Transform {
+            rows: swap_groups(a, b),
+            ..Transform::identity()
+        }
+    }
+
+    /// Swaps the columns `a` and `b` (between 0 and 2) within the given
+    /// stack (between 0 and 2)
+    pub fn swap_columns(stack: u8, a: u8, b: u8) -> Self {
+        Transform {
+            cols: swap_lines(stack, a, b),
+            ..Transform::identity()
+        }
+    }
+
+    /// Swaps the stacks (groups of 3 columns) `a` and `b`, which must be
+    /// between 0 and 2
+    pub fn swap_stacks(a: u8, b: u8) -> Self {
+        Transform {
+            cols: swap_groups(a, b),
+            ..Transform::identity()
+        }
+    }
+
+    /// Returns a transform chosen uniformly at random from all validity
+    /// preserving transforms
+    pub fn random(rng: &mut impl rand::Rng) -> Self {
+        let mut digits = IDENTITY;
+        digits.shuffle(rng);
+        Transform {
+            digits,
+            rows: random_lines(rng),
+            cols: random_lines(rng),
+            transpose: rng.random(),
+        }
+    }
+
+    /// Builds a transform from its parts. The caller must ensure that `rows`
+    /// and `cols` keep bands and stacks together.
+    pub(crate) fn from_parts(
+        digits: [u8; 9],
+        rows: [u8; 9],
+        cols: [u8; 9],
+        transpose: bool,
+    ) -> Self {
+        debug_assert!(is_permutation(&digits));
+        debug_assert!(is_line_permutation(&rows) && is_line_permutation(&cols));
+        Transform {
+            digits,
+            rows,
+            cols,
+            transpose,
+        }
+    }
+
+    /// Returns the transform that applies `self` first, and then `next`
+    pub fn then(self, next: Transform) -> Self {
+        let (rows, cols) = if next.transpose {
+            (self.cols, self.rows)
+        } else {
+            (self.rows, self.cols)
+        };
+        Transform {
+            digits: self.digits.map(|d| next.digits[d as usize]),
+            rows: next.rows.map(|r| rows[r as usize]),
+            cols: next.cols.map(|c| cols[c as usize]),
+            transpose: self.transpose != next.transpose,
+        }
+    }
+
+    /// Returns the transform that undoes `self`
+    pub fn inverse(self) -> Self {
+        let (rows, cols) = if self.transpose {
+            (self.cols, self.rows)
+        } else {
+            (self.rows, self.cols)
+        };
+        Transform {
+            digits: invert(self.digits),
+            rows: invert(rows),
+            cols: invert(cols),
+            transpose: self.transpose,
+        }
+    }
+
+    /// Applies the transform to a grid, moving all fields and relabeling
+    /// their possibilities
+    pub fn apply(&self, grid: &Grid) -> Grid {
+        let mut result = Grid::full();
+        for pos in Pos::iterator() {
+            result[pos] = self.apply_field(grid[self.source(pos)]);
+        }
+        result
+    }
+
+    /// Returns the position that the field at `pos` is moved to
+    pub fn apply_pos(&self, pos: Pos) -> Pos {
+        self.inverse().source(pos)
+    }
+
+    /// Returns the number that `n` is replaced with
+    pub fn apply_number(&self, n: Number) -> Number {
+        Number::new(self.digits[n.as_u8() as usize - 1] + 1)
+    }
+
+    /// Relabels all possibilities in `field`
+    pub fn apply_field(&self, field: Field) -> Field {
+        field
+            .possibilities()
+            .map(|n| self.apply_number(n))
+            .collect()
+    }
+
+    /// Returns the position in the input that the field at `pos` in the
+    /// output is taken from
+    fn source(&self, pos: Pos) -> Pos {
+        let row = self.rows[pos.y() as usize];
+        let col = self.cols[pos.x() as usize];
+        if self.transpose {
+            Pos::new(row, col)
+        } else {
+            Pos::new(col, row)
+        }
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+fn swap_lines(group: u8, a: u8, b: u8) -> [u8; 9] {
+    assert!(
+        group < 3 && a < 3 && b < 3,
+        "group={group}, a={a} or b={b} is too large"
+    );
+    let mut lines = IDENTITY;
+    lines.swap((group * 3 + a) as usize, (group * 3 + b) as usize);
+    lines
+}
+
+fn swap_groups(a: u8, b: u8) -> [u8; 9] {
+    assert!(a < 3 && b < 3, "a={a} or b={b} is too large");
+    let mut lines = IDENTITY;
+    for i in 0..3 {
+        lines.swap((a * 3 + i) as usize, (b * 3 + i) as usize);
+    }
+    lines
+}
+
+fn random_lines(rng: &mut impl rand::Rng) -> [u8; 9] {
+    let mut groups = [0, 1, 2];
+    groups.shuffle(rng);
+    let mut lines = [0; 9];
+    for (gi, group) in groups.into_iter().enumerate() {
+        let mut inner = [0, 1, 2];
+        inner.shuffle(rng);
+        for (li, line) in inner.into_iter().enumerate() {
+            lines[gi * 3 + li] = group * 3 + line;
+        }
+    }
+    lines
+}
+
+fn invert(perm: [u8; 9]) -> [u8; 9] {
+    let mut result = [0; 9];
+    for (i, p) in perm.into_iter().enumerate() {
+        result[p as usize] = i as u8;
+    }
+    result
+}
+
+fn is_permutation(perm: &[u8; 9]) -> bool {
+    let mut seen = 0u16;
+    for &p in perm {
+        if p > 8 {
+            return false;
+        }
+        seen |= 1 << p;
+    }
+    seen == 0b111_111_111
+}
+
+/// Whether `perm` is a permutation that keeps bands (or stacks) together
+fn is_line_permutation(perm: &[u8; 9]) -> bool {
+    is_permutation(perm)
+        && perm
+            .chunks(3)
+            .all(|group| group.iter().all(|&p| p / 3 == group[0] / 3))
+}
+
+#[test]
+fn test_compose_and_invert() {
+    let mut rng = rand::rng();
+    let grid = crate::generate(&mut rng);
+
+    let rotated = Transform::rotate_cw().then(Transform::rotate_cw());
+    assert_eq!(rotated, Transform::rotate_180());
+    assert_eq!(
+        Transform::rotate_cw().then(Transform::rotate_ccw()),
+        Transform::identity()
+    );
+
+    for _ in 0..100 {
+        let a = Transform::random(&mut rng);
+        let b = Transform::random(&mut rng);
+        assert_eq!(a.then(a.inverse()), Transform::identity());
+        assert_eq!(a.inverse().then(a), Transform::identity());
+
+        let once = a.then(b).apply(&grid);
+        assert_eq!(once, b.apply(&a.apply(&grid)));
+        for pos in Pos::iterator() {
+            assert_eq!(
+                once[a.then(b).apply_pos(pos)],
+                a.then(b).apply_field(grid[pos])
+            );
+        }
+    }
+}
+
+#[test]
+fn test_preserves_validity() {
+    let mut rng = rand::rng();
+    let grid = crate::generate(&mut rng);
+    let transformed = Transform::random(&mut rng).apply(&grid);
+
+    let mut check = Grid::full();
+    for pos in Pos::iterator() {
+        let n = transformed[pos].solution().unwrap();
+        assert!(check[pos].contains(n));
+        assert!(!check.set(pos, n));
+    }
+}
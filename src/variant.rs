@@ -0,0 +1,410 @@
+use std::fmt;
+
+use rand::seq::SliceRandom;
+
+#[cfg(test)]
+use crate::SolutionCount;
+use crate::solve::{Stats, count};
+use crate::{Field, Grid, Number, Pos, Regions};
+
+/// A rule of a sudoku variant, which applies in addition to the classic
+/// rules. Constraints are combined in a [Variant].
+///
+/// A constraint rules out possibilities by reporting them as
+/// [Eliminations], either when a number is placed, or by looking at the
+/// whole grid. It doesn't have to rule out every impossible number, because
+/// every solution is checked with [Constraint::is_valid] at the end.
+pub trait Constraint: fmt::Debug + Send + Sync {
+    /// Called after `num` was placed at `pos`. Reports the possibilities of
+    /// other fields that are ruled out by this.
+    fn on_placement(&self, grid: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        let _ = (grid, pos, num, eliminations);
+    }
+
+    /// Reports possibilities that are ruled out by the current state of the
+    /// grid. This is called repeatedly before each guess, until no more
+    /// possibilities are ruled out.
+    fn eliminations(&self, grid: &Grid, eliminations: &mut Eliminations) {
+        let _ = (grid, eliminations);
+    }
+
+    /// Returns whether a solved grid satisfies the constraint
+    fn is_valid(&self, grid: &Grid) -> bool;
+
+    /// Returns whether [generate_with_variant](crate::generate_with_variant)
+    /// has to find grids with a
+    /// randomized search, instead of placing random numbers first. This is
+    /// needed if so few grids satisfy the constraint that random numbers
+    /// almost always contradict each other.
+    fn generate_by_search(&self) -> bool {
+        false
+    }
+}
+
+/// Possibilities that are ruled out by a [Constraint].
+#[derive(Debug, Clone, Default)]
+pub struct Eliminations {
+    removed: Vec<(Pos, Field)>,
+    contradiction: bool,
+}
+
+impl Eliminations {
+    /// Rules out `num` at `pos`
+    pub fn remove(&mut self, pos: Pos, num: Number) {
+        self.removed.push((pos, num.into()));
+    }
+
+    /// Rules out all numbers at `pos` except for the ones in `allowed`
+    pub fn restrict(&mut self, pos: Pos, allowed: Field) {
+        self.removed.push((pos, !allowed));
+    }
+
+    /// Reports that the grid can't be solved, e.g. because a number can't be
+    /// placed anywhere
+    pub fn contradiction(&mut self) {
+        self.contradiction = true;
+    }
+
+    /// Returns whether nothing was ruled out
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && !self.contradiction
+    }
+
+    /// Removes the possibilities from the grid, and returns whether a
+    /// contradiction was found, and whether the grid changed
+    fn apply(
+        &mut self,
+        variant: &Variant,
+        grid: &mut Grid,
+        stats: &mut impl Stats,
+    ) -> (bool, bool) {
+        if std::mem::take(&mut self.contradiction) {
+            self.removed.clear();
+            return (true, false);
+        }
+        let mut changed = false;
+        for (pos, removed) in self.removed.drain(..) {
+            let field = grid[pos];
+            let remaining = field - removed;
+            if remaining == field {
+                continue;
+            }
+            changed = true;
+            if remaining.is_empty() {
+                return (true, changed);
+            } else if let Some(num) = remaining.solution() {
+                if variant.place(grid, pos, num, stats) {
+                    return (true, changed);
+                }
+            } else {
+                grid[pos] = remaining;
+            }
+        }
+        (false, changed)
+    }
+}
+
+/// A sudoku variant: The classic rules, plus a set of [Constraint]s. The 3x3
+/// blocks can be replaced with irregular [Regions].
+///
+/// Variant sudokus are solved and generated with
+/// [solve_with_variant](crate::solve_with_variant),
+/// [count_solutions_with_variant](crate::count_solutions_with_variant) and
+/// [generate_with_variant](crate::generate_with_variant). Grids should be
+/// created with [Variant::new_grid] and changed with [Variant::set], since
+/// [Grid::new] and [Grid::set] only know the classic rules.
+///
+/// ### Example
+///
+/// ```
+/// use sudoku::{Constraint, Grid, Pos, Variant, generate_with_variant};
+///
+/// /// The top left field must be even
+/// #[derive(Debug)]
+/// struct EvenCorner;
+///
+/// impl Constraint for EvenCorner {
+///     fn is_valid(&self, grid: &Grid) -> bool {
+///         grid[Pos::new(0, 0)].solution().is_some_and(|n| n.as_u8() % 2 == 0)
+///     }
+/// }
+///
+/// let variant = Variant::new().with(EvenCorner);
+/// let grid = generate_with_variant(&variant, &mut rand::rng());
+/// assert!(variant.is_valid(&grid));
+/// ```
+#[derive(Debug, Default)]
+pub struct Variant {
+    regions: Option<Regions>,
+    constraints: Vec<Box<dyn Constraint>>,
+}
+
+impl Variant {
+    /// Creates a variant with only the classic rules
+    pub fn new() -> Self {
+        Variant::default()
+    }
+
+    /// Adds a constraint
+    pub fn with(mut self, constraint: impl Constraint + 'static) -> Self {
+        self.constraints.push(Box::new(constraint));
+        self
+    }
+
+    /// Replaces the 3x3 blocks with irregular regions
+    pub fn with_regions(mut self, regions: Regions) -> Self {
+        self.regions = Some(regions);
+        self
+    }
+
+    /// Returns all constraints in addition to the classic rules
+    pub fn constraints(&self) -> &[Box<dyn Constraint>] {
+        &self.constraints
+    }
+
+    /// Returns the regions that replace the 3x3 blocks, if any
+    pub fn regions(&self) -> Option<&Regions> {
+        self.regions.as_ref()
+    }
+
+    /// Whether this variant only has the classic rules
+    pub(crate) fn is_classic(&self) -> bool {
+        self.regions.is_none() && self.constraints.is_empty()
+    }
+
+    /// Whether a constraint needs a randomized search to generate grids
+    pub(crate) fn generate_by_search(&self) -> bool {
+        self.constraints.iter().any(|c| c.generate_by_search())
+    }
+
+    /// Creates a sudoku grid like [Grid::new], but rules out all
+    /// possibilities that contradict the rules of this variant
+    pub fn new_grid(&self, fields: [[Field; 9]; 9]) -> Grid {
+        let mut grid = Grid { fields };
+        for pos in Pos::iterator() {
+            if let Some(solution) = grid[pos].solution() {
+                self.place(&mut grid, pos, solution, &mut ());
+            }
+        }
+        self.propagate(&mut grid, &Grid::full(), &mut ());
+        grid
+    }
+
+    /// Sets the field at the given position to the given number like
+    /// [Grid::set], and rules out all possibilities that contradict the
+    /// constraints. Returns whether any field is now empty.
+    pub fn set(&self, grid: &mut Grid, pos: Pos, num: Number) -> bool {
+        if self.is_classic() {
+            return grid.set(pos, num);
+        }
+        let before = grid.clone();
+        self.place(grid, pos, num, &mut ()) || self.propagate(grid, &before, &mut ())
+    }
+
+    /// Sets the field like [Grid::set], but with the regions of this variant
+    /// instead of the 3x3 blocks. Constraints are not considered.
+    pub(crate) fn place(
+        &self,
+        grid: &mut Grid,
+        pos: Pos,
+        num: Number,
+        stats: &mut impl Stats,
+    ) -> bool {
+        match &self.regions {
+            None => grid.set_with_stats(pos, num, stats),
+            Some(regions) => {
+                grid.set_with_peers(pos, num, &|pos| regions.visible_positions(pos), stats)
+            }
+        }
+    }
+
+    /// Returns whether the grid is solved and follows all rules of this
+    /// variant: Every row, column and block or region contains every number
+    /// once, and all constraints are satisfied.
+    pub fn is_valid(&self, grid: &Grid) -> bool {
+        self.houses().iter().all(|house| {
+            let numbers: Field = house
+                .iter()
+                .filter_map(|&pos| grid[pos].solution())
+                .collect();
+            numbers == Field::full()
+        }) && self.constraints.iter().all(|c| c.is_valid(grid))
+    }
+
+    /// Returns the rows, columns and blocks or regions
+    fn houses(&self) -> &[[Pos; 9]] {
+        match &self.regions {
+            Some(regions) => regions.houses(),
+            None => crate::houses::classic_houses(),
+        }
+    }
+
+    /// Rules out the numbers of the solved fields of `grid` in their regions
+    /// like [Variant::new_grid], keeping all other possibilities, and
+    /// propagates the constraints. Returns whether a contradiction was found.
+    pub(crate) fn place_solved(&self, grid: &mut Grid, stats: &mut impl Stats) -> bool {
+        for pos in Pos::iterator() {
+            if let Some(num) = grid[pos].solution()
+                && self.place(grid, pos, num, stats)
+            {
+                return true;
+            }
+        }
+        self.propagate(grid, &Grid::full(), stats)
+    }
+
+    /// Removes givens from a solved grid in random order, as long as the
+    /// sudoku stays uniquely solvable under the rules of this variant. The
+    /// constraints, e.g. [Restrictions](crate::Restrictions), often make
+    /// many more givens unnecessary than in a classic sudoku.
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use sudoku::{SolutionCount, Variant, count_solutions_with_variant, generate_with_variant};
+    ///
+    /// let mut rng = rand::rng();
+    /// let variant = Variant::sudoku_x();
+    /// let solution = generate_with_variant(&variant, &mut rng);
+    ///
+    /// let puzzle = variant.remove_givens(&solution, &mut rng);
+    /// let count = count_solutions_with_variant(&puzzle, &variant, 2);
+    /// assert_eq!(count, SolutionCount::Exact(1));
+    /// ```
+    pub fn remove_givens(&self, solution: &Grid, rng: &mut impl rand::Rng) -> Grid {
+        let mut positions: Vec<Pos> = Pos::iterator().collect();
+        positions.shuffle(rng);
+
+        let mut givens = solution.fields;
+        for pos in positions {
+            let (x, y) = (pos.x() as usize, pos.y() as usize);
+            let given = givens[y][x];
+            givens[y][x] = Field::full();
+            if !count(&self.new_grid(givens), Some(self), 2).is_unique() {
+                givens[y][x] = given;
+            }
+        }
+        self.new_grid(givens)
+    }
+
+    /// Rules out possibilities of `grid` until no constraint reports any more
+    /// eliminations. Fields that are solved in `grid`, but not in `before`,
+    /// are passed to [Constraint::on_placement]. Returns whether a
+    /// contradiction was found.
+    pub(crate) fn propagate(&self, grid: &mut Grid, before: &Grid, stats: &mut impl Stats) -> bool {
+        if self.is_classic() {
+            return false;
+        }
+        let mut before = before.clone();
+        let mut eliminations = Eliminations::default();
+        loop {
+            for pos in Pos::iterator() {
+                if let Some(num) = grid[pos].solution()
+                    && before[pos].solution().is_none()
+                {
+                    for constraint in &self.constraints {
+                        constraint.on_placement(grid, pos, num, &mut eliminations);
+                    }
+                }
+            }
+            before.clone_from(grid);
+            let (contradiction, changed) = eliminations.apply(self, grid, stats);
+            if contradiction {
+                return true;
+            } else if changed {
+                continue;
+            }
+
+            // the search only places naked singles, which is enough for
+            // classic sudokus, but variants are solved much faster when
+            // hidden singles are placed as well
+            for house in self.houses() {
+                crate::houses::hidden_singles(grid, house, &mut eliminations);
+            }
+            for constraint in &self.constraints {
+                constraint.eliminations(grid, &mut eliminations);
+            }
+            let (contradiction, changed) = eliminations.apply(self, grid, stats);
+            if contradiction {
+                return true;
+            } else if !changed {
+                return false;
+            }
+        }
+    }
+}
+
+/// Removes the possibilities that `report` rules out from a copy of `grid`,
+/// without placing any numbers. Returns `None` if a contradiction was
+/// reported.
+#[cfg(test)]
+pub(crate) fn eliminated(grid: &Grid, report: impl FnOnce(&mut Eliminations)) -> Option<Grid> {
+    let mut eliminations = Eliminations::default();
+    report(&mut eliminations);
+    if eliminations.contradiction {
+        return None;
+    }
+    let mut grid = grid.clone();
+    for (pos, removed) in eliminations.removed {
+        grid[pos] -= removed;
+    }
+    Some(grid)
+}
+
+/// Generates a grid for `variant` from a fixed seed, and checks that it
+/// follows the rules and is uniquely solvable
+#[cfg(test)]
+pub(crate) fn check_generated(variant: &Variant, seed: u64) -> Grid {
+    use rand::SeedableRng;
+
+    let grid = crate::generate_with_variant(variant, &mut rand::rngs::StdRng::seed_from_u64(seed));
+    assert!(variant.is_valid(&grid));
+    assert_eq!(count(&grid, Some(variant), 2), SolutionCount::Exact(1));
+    grid
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+struct DistinctDiagonal;
+
+#[cfg(test)]
+impl Constraint for DistinctDiagonal {
+    fn on_placement(&self, _: &Grid, pos: Pos, num: Number, eliminations: &mut Eliminations) {
+        if pos.x() == pos.y() {
+            for i in (0..9).filter(|&i| i != pos.x()) {
+                eliminations.remove(Pos::new(i, i), num);
+            }
+        }
+    }
+
+    fn is_valid(&self, grid: &Grid) -> bool {
+        let diagonal: Field = (0..9)
+            .filter_map(|i| grid[Pos::new(i, i)].solution())
+            .collect();
+        diagonal == Field::full()
+    }
+}
+
+#[test]
+fn test_variant() {
+    let variant = Variant::new().with(DistinctDiagonal);
+    check_generated(&variant, 0);
+
+    // every house must contain every number once
+    let classic = Variant::new();
+    assert!(classic.is_valid(&crate::generate_from_seed(0)));
+    assert!(!classic.is_valid(&Grid::full()));
+    let ones = Grid {
+        fields: [[Field::new(1); 9]; 9],
+    };
+    assert!(!classic.is_valid(&ones));
+
+    // valid without the constraint, but the diagonal contains 1 twice
+    let mut grid = Grid::full();
+    for (x, n) in [1, 2, 3, 4, 5, 6, 7, 8, 9].into_iter().enumerate() {
+        grid.set(Pos::new(x as u8, 0), Number::new(n));
+    }
+    grid.set(Pos::new(8, 8), Number::new(1));
+    assert!(count(&grid, Some(&Variant::new()), 1) != SolutionCount::Exact(0));
+    assert_eq!(count(&grid, Some(&variant), 1), SolutionCount::Exact(0));
+}